@@ -1,6 +1,35 @@
+use std::collections::HashMap;
 use std::error;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, Offset, Utc};
+use ratatui::layout::Rect;
 use ratatui::widgets::ListState;
-use crate::connection::CityInfo;
+use tokio::sync::{mpsc, Semaphore};
+use crate::cache::WeatherCache;
+use crate::cities::{self, CityGroup, CityRow, EditHistory, ListSnapshot};
+use crate::config::Config;
+use crate::event::Event;
+use crate::fetch::{CityWeather, WeatherFetcher};
+use crate::i18n::{fill, LocaleSettings, Messages};
+use crate::icons::{self, GraphicsProtocol, Icon};
+use crate::refresh::RefreshSettings;
+use crate::secrets::{write_key_file, ApiKeySettings};
+use crate::setup::{self, Setup, SetupStep};
+use crate::units::{UnitSettings, Units};
+use crate::connection::{
+    GeoLocation, History, HttpClient, Location, LocationQuery, WeatherAlert, WeatherError, WeatherProvider,
+};
+
+/// Most cities fetched at the same time.
+pub const MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// How long a toast stays in the status line.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Frames of the loading spinner shown next to cities being fetched.
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
 /// Application result type.
 pub type AppResult<T> = Result<T, Box<dyn error::Error>>;
@@ -10,6 +39,32 @@ pub type AppResult<T> = Result<T, Box<dyn error::Error>>;
 pub enum InputMode {
     Normal,
     Editing,
+    /// Picking one of the geocoding candidates for the typed city
+    Selecting,
+    /// Reading the details of the selected city's weather alerts
+    Alerts,
+    /// Browsing the past weather of the selected city
+    History,
+    /// Going through the first-run wizard
+    Setup,
+    /// Naming the group of the selected city, or renaming the selected group
+    Grouping,
+    /// Renaming the selected city
+    Renaming,
+}
+
+/// Fetch state of one city.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum WeatherState {
+    /// Never fetched
+    #[default]
+    NotLoaded,
+    /// A fetch is in flight
+    Loading,
+    /// The last fetch succeeded at the given time
+    Loaded(DateTime<Utc>),
+    /// The last fetch failed
+    Failed(WeatherError),
 }
 
 /// Application.
@@ -17,81 +72,347 @@ pub enum InputMode {
 pub struct App {
     /// Is the application running?
     pub running: bool,
-    /// API key for OpenWeather
+    /// API key for OpenWeather, empty for services that need none
     pub api_key: String,
+    /// HTTP client shared by all requests, also reports retry state
+    pub http: HttpClient,
+    /// Weather service used for all fetches
+    pub provider: Arc<dyn WeatherProvider>,
+    /// On-disk cache of fetched data, if enabled
+    pub cache: Option<WeatherCache>,
+    /// Only show cached data, never contact the service
+    pub offline: bool,
     /// Current input mode
     pub input_mode: InputMode,
     /// Current input value when adding a new city
     pub input: String,
+    /// Places matching the typed city, shown for disambiguation
+    pub candidates: Vec<GeoLocation>,
+    /// Currently highlighted candidate index
+    pub selected_candidate: usize,
+    /// Feedback shown in the Add City popup (searching, no match, errors)
+    pub input_status: Option<String>,
+    /// Flag to indicate a geocoding lookup of the input is requested
+    pub geocode_requested: bool,
     /// List of cities to display
-    pub cities: Vec<String>,
+    pub cities: Vec<Location>,
     /// Currently selected city index
     pub selected_city: usize,
-    /// Current weather data
-    pub current_weather: Option<CityInfo>,
-    /// Flag to indicate weather fetch is requested
+    /// Named sections of the city list, in list order
+    pub groups: Vec<CityGroup>,
+    /// Index of the group whose header is selected; the selected city stays shown meanwhile
+    pub selected_group: Option<usize>,
+    /// Where the city list was last drawn and its scroll offset, for mapping mouse positions to lines
+    pub city_list_area: Option<(Rect, usize)>,
+    /// The city list as it was when the selected city was picked up with the mouse, while it is dragged
+    pub drag_origin: Option<ListSnapshot>,
+    /// Edits of the city list that can be undone and redone
+    pub edits: EditHistory,
+    /// Short feedback in the status line, e.g. what an undo did, and when it was shown
+    pub toast: Option<(String, Instant)>,
+    /// Fetch state of each city, keyed by [`Location::key`]
+    pub weather_states: HashMap<String, WeatherState>,
+    /// Last data fetched for each city, kept while it is refreshed
+    pub city_weather: HashMap<String, CityWeather>,
+    /// Lines scrolled down in the alert details popup
+    pub alert_scroll: u16,
+    /// Day shown in the history view
+    pub history_date: NaiveDate,
+    /// Observations of the selected city on [`App::history_date`], once loaded
+    pub history: Option<History>,
+    /// Fetch state of the history view
+    pub history_state: WeatherState,
+    /// Flag to indicate the history of the selected city should be fetched
+    pub history_requested: bool,
+    /// How condition icons are drawn
+    pub icon_protocol: GraphicsProtocol,
+    /// Condition icons by ID; `None` while loading or when only the glyph is available
+    pub icons: HashMap<String, Option<Arc<Icon>>>,
+    /// Icon IDs waiting to be loaded in the background
+    pub pending_icons: Vec<String>,
+    /// Icon to draw over the frame and where, for protocols that cannot draw into the buffer
+    pub icon_placement: Option<(Arc<Icon>, Rect)>,
+    /// Ticks since start, drives the loading spinner
+    pub ticks: u64,
+    /// Cities waiting to be handed to a background fetch, and whether to bypass fresh cache entries
+    pub pending_fetches: Vec<(Location, bool)>,
+    /// Bounds the number of fetches running at the same time
+    pub fetch_limit: Arc<Semaphore>,
+    /// Automatic refresh policy
+    pub refresh: RefreshSettings,
+    /// When each city is due for its next automatic refresh, keyed by [`Location::key`]
+    pub next_refresh: HashMap<String, Instant>,
+    /// Flag to indicate the pending fetches should be started
     pub fetch_requested: bool,
+    /// Units values are displayed in, switchable at runtime
+    pub unit_settings: UnitSettings,
+    /// Language and clock format of the UI
+    pub locale: LocaleSettings,
+    /// Settings as loaded; the parts changed at runtime are taken from the app when saving
+    pub config: Config,
+    /// File the settings are saved to; nothing is saved if `None`
+    pub config_path: Option<PathBuf>,
+    /// Flag to indicate the settings changed and should be saved
+    pub config_changed: bool,
+    /// Flag to indicate another city was selected; only saved on quit, not on every key press
+    pub selection_changed: bool,
+    /// Why the settings could not be saved, shown in the status line
+    pub config_error: Option<String>,
+    /// Choices made in the first-run wizard
+    pub setup: Setup,
     /// Terminal size
     pub terminal_size: Option<(u16, u16)>,
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl App {
     /// Constructs a new instance of [`App`].
     pub fn new() -> Self {
-        Self {
+        Self::from_config(&Config::default(), String::new())
+    }
+    
+    /// Constructs a new instance of [`App`] using the given configuration and API key.
+    pub fn from_config(config: &Config, api_key: String) -> Self {
+        let http = HttpClient::new(&config.http);
+        let mut app = Self {
             running: true,
-            api_key: String::from("5d916a464e1dced7b9b26a4454d37d40"),
+            provider: config.provider.build(&api_key, config.locale.language, &config.endpoints, http.clone()),
+            http,
+            cache: WeatherCache::open(&config.cache),
+            offline: false,
+            api_key,
             input_mode: InputMode::Normal,
             input: String::new(),
-            cities: vec![
-                "Bucharest".to_string(),
-                "London".to_string(),
-                "New York".to_string(),
-                "Budapest".to_string(),
-                "Tokyo".to_string(),
-                "Paris".to_string(),
-                "Berlin".to_string(),
-                "Moscow".to_string(),
-                "Sydney".to_string(),
-                "Toronto".to_string(),
-            ],
-            selected_city: 0,
-            current_weather: None,
+            candidates: Vec::new(),
+            selected_candidate: 0,
+            input_status: None,
+            geocode_requested: false,
+            cities: config.cities.clone(),
+            selected_city: config.selected_city.min(config.cities.len().saturating_sub(1)),
+            groups: config.groups.clone(),
+            selected_group: None,
+            city_list_area: None,
+            drag_origin: None,
+            edits: EditHistory::default(),
+            toast: None,
+            weather_states: HashMap::new(),
+            city_weather: HashMap::new(),
+            alert_scroll: 0,
+            history_date: Utc::now().date_naive() - Days::new(1),
+            history: None,
+            history_state: WeatherState::NotLoaded,
+            history_requested: false,
+            icon_protocol: config.icons.protocol(),
+            icons: HashMap::new(),
+            pending_icons: Vec::new(),
+            icon_placement: None,
+            ticks: 0,
+            pending_fetches: Vec::new(),
+            fetch_limit: Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES)),
+            refresh: config.refresh.clone(),
+            next_refresh: HashMap::new(),
             fetch_requested: false,
+            unit_settings: config.units.clone(),
+            locale: config.locale.clone(),
+            config: config.clone(),
+            config_path: None,
+            config_changed: false,
+            selection_changed: false,
+            config_error: None,
+            setup: Setup::default(),
             terminal_size: None,
-        }
+        };
+        app.arrange_cities();
+        app
     }
     
     /// Returns the ListState for the city list
     pub fn list_state(&mut self) -> ListState {
         let mut state = ListState::default();
-        state.select(Some(self.selected_city));
+        state.select(self.selected_row());
         state
     }
     
+    /// Lines of the city list, with the cities of collapsed groups left out
+    pub fn city_rows(&self) -> Vec<CityRow> {
+        cities::rows(&self.cities, &self.groups)
+    }
+    
+    /// Index of the selected line in [`App::city_rows`]
+    ///
+    /// While the selected city is in a collapsed group, its group header stands in for it.
+    pub fn selected_row(&self) -> Option<usize> {
+        let rows = self.city_rows();
+        let selected = match self.selected_group {
+            Some(group) => CityRow::Group(group),
+            None => CityRow::City(self.selected_city),
+        };
+        rows.iter().position(|&row| row == selected).or_else(|| {
+            let group = self.selected_city_group()?;
+            rows.iter().position(|&row| row == CityRow::Group(group))
+        })
+    }
+    
+    /// Index of the group the selected line belongs to; pinned cities belong to none
+    fn selected_city_group(&self) -> Option<usize> {
+        if self.selected_group.is_some() {
+            return self.selected_group;
+        }
+        let city = self.cities.get(self.selected_city).filter(|city| !city.pinned)?;
+        self.groups.iter().position(|group| city.group.as_ref() == Some(&group.name))
+    }
+    
+    /// Select a line of the city list
+    fn select_row(&mut self, row: CityRow) {
+        match row {
+            CityRow::Group(group) => self.selected_group = Some(group),
+            CityRow::City(index) => {
+                self.selected_group = None;
+                self.selected_city = index;
+                self.selection_changed = true;
+                self.fetch_selected_if_needed();
+            }
+        }
+    }
+    
+    /// Sort the cities into list order after a change, keeping the same city selected
+    fn arrange_cities(&mut self) {
+        self.selected_city = cities::arrange(&mut self.cities, &mut self.groups, self.selected_city);
+        self.selected_group = self.selected_group.filter(|&group| group < self.groups.len());
+        self.select_visible();
+    }
+    
+    /// Select the header of a collapsed group rather than a city hidden in it
+    ///
+    /// Otherwise the header is highlighted while keys act on the hidden city.
+    fn select_visible(&mut self) {
+        if self.selected_group.is_none() {
+            self.selected_group = self.selected_city_group().filter(|&group| self.groups[group].collapsed);
+        }
+    }
+    
     /// Enter edit mode to add a new city
     pub fn enter_edit_mode(&mut self) {
         self.input_mode = InputMode::Editing;
         self.input.clear();
+        self.input_status = None;
     }
     
     /// Exit edit mode without adding a city
     pub fn exit_edit_mode(&mut self) {
         self.input_mode = InputMode::Normal;
         self.input.clear();
+        self.candidates.clear();
+        self.input_status = None;
     }
     
-    /// Add the current input as a new city
+    /// Request a geocoding lookup for the current input
+    ///
+    /// Coordinates, zip codes and city IDs need no lookup and are added right away.
+    pub fn search_city(&mut self) {
+        if let Some(query) = LocationQuery::parse(&self.input) {
+            self.push_city(Location::from_query(query));
+            self.exit_edit_mode();
+        } else if !self.input.trim().is_empty() {
+            self.input_status = Some(self.messages().searching.to_string());
+            self.geocode_requested = true;
+        }
+    }
+    
+    /// Start looking up the current input in the background
+    ///
+    /// The matches come back as [`Event::GeocodeLoaded`] / [`Event::GeocodeFailed`] through `sender`.
+    pub fn geocode_input(&mut self, sender: &mpsc::UnboundedSender<Event>) {
+        self.fetcher().spawn_geocode(self.input.trim().to_string(), sender.clone());
+        self.geocode_requested = false;
+    }
+    
+    /// Offer the places found for `query` for selection, unless the input has changed meanwhile
+    pub fn geocode_loaded(&mut self, query: String, candidates: Vec<GeoLocation>) {
+        if !self.is_current_query(&query) {
+            return;
+        }
+        if candidates.is_empty() {
+            self.input_status = Some(fill(self.messages().no_match, &[&query]));
+            return;
+        }
+        self.candidates = candidates;
+        self.selected_candidate = 0;
+        self.input_status = None;
+        // The wizard shows the candidates on its own page
+        if self.input_mode == InputMode::Editing {
+            self.input_mode = InputMode::Selecting;
+        }
+    }
+    
+    /// Show why `query` could not be looked up, unless the input has changed meanwhile
+    pub fn geocode_failed(&mut self, query: String, error: WeatherError) {
+        if self.is_current_query(&query) {
+            self.input_status = Some(format!("{} - {}", error.message(self.messages()), error.suggestion(self.messages())));
+        }
+    }
+    
+    /// Whether a city is being typed and the input still reads `query`
+    fn is_current_query(&self, query: &str) -> bool {
+        let typing = match self.input_mode {
+            InputMode::Editing => true,
+            InputMode::Setup => self.setup.step == SetupStep::HomeCity,
+            _ => false,
+        };
+        typing && self.input.trim() == query
+    }
+    
+    /// Highlight the next geocoding candidate
+    pub fn next_candidate(&mut self) {
+        if !self.candidates.is_empty() {
+            self.selected_candidate = (self.selected_candidate + 1) % self.candidates.len();
+        }
+    }
+    
+    /// Highlight the previous geocoding candidate
+    pub fn previous_candidate(&mut self) {
+        if !self.candidates.is_empty() {
+            self.selected_candidate = if self.selected_candidate > 0 {
+                self.selected_candidate - 1
+            } else {
+                self.candidates.len() - 1
+            };
+        }
+    }
+    
+    /// Go back from the candidate list to typing
+    pub fn back_to_editing(&mut self) {
+        self.candidates.clear();
+        self.input_mode = InputMode::Editing;
+    }
+    
+    /// Add the highlighted candidate as a new city
     pub fn add_city(&mut self) {
-        let trimmed = self.input.trim();
-        if !trimmed.is_empty() {
-            self.cities.push(trimmed.to_string());
-            self.selected_city = self.cities.len() - 1;
-            // Request weather data for the newly added city
-            self.request_weather_fetch();
+        if let Some(place) = self.candidates.get(self.selected_candidate).cloned() {
+            self.push_city(Location::from(place));
         }
-        self.input_mode = InputMode::Normal;
-        self.input.clear();
+        self.exit_edit_mode();
+    }
+    
+    /// Append a city to the list and select it
+    ///
+    /// It joins the group of the selected line, if any.
+    fn push_city(&mut self, mut location: Location) {
+        let before = self.snapshot();
+        let label = fill(self.messages().edit_added, &[&location.name]);
+        location.group = self.selected_city_group().map(|group| self.groups[group].name.clone());
+        self.cities.push(location);
+        self.selected_city = self.cities.len() - 1;
+        self.selected_group = None;
+        self.arrange_cities();
+        self.commit_edit(before, label);
+        // Request weather data for the newly added city
+        self.fetch_selected_if_needed();
     }
     
     /// Handle keyboard input when in edit mode
@@ -104,51 +425,782 @@ impl App {
         self.input.pop();
     }
     
-    /// Navigate to the next city
+    /// Navigate to the next line of the city list
     pub fn next_city(&mut self) {
-        if !self.cities.is_empty() {
-            self.selected_city = (self.selected_city + 1) % self.cities.len();
-            // Request weather data for the newly selected city
-            self.request_weather_fetch();
+        let rows = self.city_rows();
+        if let Some(current) = self.selected_row() {
+            self.select_row(rows[(current + 1) % rows.len()]);
         }
     }
     
-    /// Navigate to the previous city
+    /// Navigate to the previous line of the city list
     pub fn previous_city(&mut self) {
-        if !self.cities.is_empty() {
-            self.selected_city = if self.selected_city > 0 {
-                self.selected_city - 1
-            } else {
-                self.cities.len() - 1
+        let rows = self.city_rows();
+        if let Some(current) = self.selected_row() {
+            self.select_row(rows[if current > 0 { current - 1 } else { rows.len() - 1 }]);
+        }
+    }
+    
+    /// Move the selected city one line up (`delta` -1) or down (1), or the selected group past its neighbour
+    ///
+    /// A city moved past a group header joins or leaves that group.
+    pub fn move_selected(&mut self, delta: isize) {
+        let before = self.snapshot();
+        if let Some(group) = self.selected_group {
+            if let Some(other) = group.checked_add_signed(delta).filter(|&other| other < self.groups.len()) {
+                let label = fill(self.messages().edit_moved_group, &[&self.groups[group].name]);
+                self.groups.swap(group, other);
+                self.selected_group = Some(other);
+                self.arrange_cities();
+                self.commit_edit(before, label);
+            }
+            return;
+        }
+        let rows = self.city_rows();
+        let Some(current) = rows.iter().position(|&row| row == CityRow::City(self.selected_city)) else {
+            return;
+        };
+        if let Some(target) = current.checked_add_signed(delta).filter(|&target| target < rows.len()) {
+            let label = fill(self.messages().edit_moved, &[&self.cities[self.selected_city].name]);
+            self.move_selected_to_row(target);
+            self.commit_edit(before, label);
+        }
+    }
+    
+    /// Move the selected city to a line of the city list
+    fn move_selected_to_row(&mut self, target: usize) {
+        if self.selected_city < self.cities.len() {
+            self.selected_city = cities::move_to_row(&mut self.cities, &mut self.groups, self.selected_city, target);
+            self.config_changed = true;
+        }
+    }
+    
+    /// Pin the selected city to the top of the list, or unpin it
+    pub fn toggle_pin(&mut self) {
+        if self.selected_group.is_some() {
+            return;
+        }
+        let before = self.snapshot();
+        if let Some(city) = self.cities.get_mut(self.selected_city) {
+            city.pinned = !city.pinned;
+            let messages = self.locale.messages();
+            let label = fill(if city.pinned { messages.edit_pinned } else { messages.edit_unpinned }, &[&city.name]);
+            self.arrange_cities();
+            self.commit_edit(before, label);
+        }
+    }
+    
+    /// Collapse or expand the selected group, or the group of the selected city
+    pub fn toggle_group(&mut self) {
+        if let Some(index) = self.selected_city_group() {
+            let group = &mut self.groups[index];
+            group.collapsed = !group.collapsed;
+            // The selected city is hidden now, so its header takes over the selection
+            if group.collapsed {
+                self.selected_group = Some(index);
+            }
+            self.config_changed = true;
+        }
+    }
+    
+    /// Open the popup naming the group of the selected city, or renaming the selected group
+    pub fn open_group_input(&mut self) {
+        if self.cities.is_empty() {
+            return;
+        }
+        let name = match self.selected_group {
+            Some(group) => Some(&self.groups[group].name),
+            None => self.cities[self.selected_city].group.as_ref(),
+        };
+        self.input = name.cloned().unwrap_or_default();
+        self.input_status = None;
+        self.input_mode = InputMode::Grouping;
+    }
+    
+    /// Apply the typed group name
+    ///
+    /// For a city it files the city at the end of that group; for a group header it renames the group,
+    /// merging it into another group of that name. An empty name ungroups.
+    pub fn apply_group_input(&mut self) {
+        let before = self.snapshot();
+        let messages = self.messages();
+        let name = Some(self.input.trim().to_string()).filter(|name| !name.is_empty());
+        let label = match self.selected_group {
+            Some(index) => {
+                let old = self.groups[index].name.clone();
+                let label = match &name {
+                    Some(name) => fill(messages.edit_renamed_group, &[&old, name]),
+                    None => fill(messages.edit_dissolved_group, &[&old]),
+                };
+                for city in self.cities.iter_mut().filter(|city| city.group.as_ref() == Some(&old)) {
+                    city.group = name.clone();
+                }
+                match &name {
+                    Some(name) if !self.groups.iter().any(|group| &group.name == name) => {
+                        self.groups[index].name = name.clone();
+                    }
+                    // Left empty, so it is dropped when arranging
+                    _ => self.selected_group = None,
+                }
+                self.arrange_cities();
+                if self.selected_group.is_none() {
+                    self.selected_group = name.and_then(|name| self.groups.iter().position(|group| group.name == name));
+                }
+                label
+            }
+            None if self.selected_city < self.cities.len() => {
+                let mut city = self.cities.remove(self.selected_city);
+                let label = match &name {
+                    Some(name) => fill(messages.edit_grouped, &[&city.name, name]),
+                    None => fill(messages.edit_ungrouped, &[&city.name]),
+                };
+                city.group = name;
+                self.cities.push(city);
+                self.selected_city = self.cities.len() - 1;
+                self.arrange_cities();
+                label
+            }
+            None => String::new(),
+        };
+        self.commit_edit(before, label);
+        self.exit_edit_mode();
+    }
+    
+    /// Open the popup renaming the selected city
+    pub fn open_rename_input(&mut self) {
+        if self.selected_group.is_some() {
+            return;
+        }
+        if let Some(city) = self.cities.get(self.selected_city) {
+            self.input = city.name.clone();
+            self.input_status = None;
+            self.input_mode = InputMode::Renaming;
+        }
+    }
+    
+    /// Give the selected city the typed name; an empty name keeps the old one
+    ///
+    /// Only the name shown changes, the place it is looked up by stays the same.
+    pub fn apply_rename_input(&mut self) {
+        let before = self.snapshot();
+        let name = self.input.trim().to_string();
+        if let Some(city) = self.cities.get_mut(self.selected_city).filter(|_| !name.is_empty()) {
+            let label = fill(self.locale.messages().edit_renamed, &[&city.name, &name]);
+            city.name = name;
+            self.follow_renames(&before.cities);
+            self.commit_edit(before, label);
+        }
+        self.exit_edit_mode();
+    }
+    
+    /// The line of the city list at a terminal position, if the list is there
+    fn city_row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let (area, offset) = self.city_list_area?;
+        if column < area.x || column >= area.right() || row < area.y || row >= area.bottom() {
+            return None;
+        }
+        let line = offset + usize::from(row - area.y);
+        (line < self.city_rows().len()).then_some(line)
+    }
+    
+    /// Handle a click on the city list: select a city and start dragging it, or fold a group
+    pub fn click_city_list(&mut self, column: u16, row: u16) {
+        let Some(line) = self.city_row_at(column, row) else {
+            return;
+        };
+        match self.city_rows()[line] {
+            CityRow::Group(group) => {
+                self.selected_group = Some(group);
+                self.toggle_group();
+            }
+            CityRow::City(index) => {
+                self.select_row(CityRow::City(index));
+                self.drag_origin = Some(self.snapshot());
+            }
+        }
+    }
+    
+    /// Move the dragged city to the line under the mouse
+    ///
+    /// Above or below the list it goes to the first or last visible line.
+    pub fn drag_city(&mut self, row: u16) {
+        // Nothing to aim at while the list has no room, e.g. in a tiny terminal
+        let Some((area, offset)) = self.city_list_area.filter(|(area, _)| self.drag_origin.is_some() && area.height > 0) else {
+            return;
+        };
+        let rows = self.city_rows();
+        let y = row.clamp(area.y, area.bottom().saturating_sub(1));
+        let target = (offset + usize::from(y - area.y)).min(rows.len().saturating_sub(1));
+        if rows.get(target) != Some(&CityRow::City(self.selected_city)) {
+            self.move_selected_to_row(target);
+        }
+    }
+    
+    /// Drop the dragged city; the whole drag is undone at once
+    pub fn end_drag(&mut self) {
+        if let Some(before) = self.drag_origin.take() {
+            let label = match self.cities.get(self.selected_city) {
+                Some(city) => fill(self.messages().edit_moved, &[&city.name]),
+                None => String::new(),
             };
-            // Request weather data for the newly selected city
-            self.request_weather_fetch();
+            self.commit_edit(before, label);
+        }
+    }
+    
+    /// The city list as edits change it
+    fn snapshot(&self) -> ListSnapshot {
+        ListSnapshot {
+            cities: self.cities.clone(),
+            groups: self.groups.clone(),
+            selected_city: self.selected_city,
+            selected_group: self.selected_group,
+        }
+    }
+    
+    /// Record an edit of the city list for undo, unless it changed nothing, and offer to undo it
+    fn commit_edit(&mut self, before: ListSnapshot, label: String) {
+        if before.cities == self.cities && before.groups == self.groups {
+            return;
+        }
+        self.edits.record(before, label.clone());
+        self.show_toast(fill(self.messages().undo_hint, &[&label]));
+        self.config_changed = true;
+    }
+    
+    /// Undo the last edit of the city list
+    pub fn undo(&mut self) {
+        match self.edits.undo(self.snapshot()) {
+            Some((snapshot, label)) => {
+                self.restore(snapshot);
+                self.show_toast(fill(self.messages().undone, &[&label]));
+            }
+            None => self.show_toast(self.messages().nothing_to_undo.to_string()),
         }
     }
     
+    /// Redo the last undone edit of the city list
+    pub fn redo(&mut self) {
+        match self.edits.redo(self.snapshot()) {
+            Some((snapshot, label)) => {
+                self.restore(snapshot);
+                self.show_toast(fill(self.messages().redone, &[&label]));
+            }
+            None => self.show_toast(self.messages().nothing_to_redo.to_string()),
+        }
+    }
+    
+    /// Move the refresh overrides of cities whose name differs from the one they had in `before`
+    ///
+    /// Overrides are matched by name, so without this a renamed city would fall back to the default interval.
+    fn follow_renames(&mut self, before: &[Location]) {
+        for city in &self.cities {
+            if let Some(old) = before.iter().find(|old| old.key() == city.key() && old.name != city.name) {
+                self.refresh.rename(&old.name, &city.name);
+            }
+        }
+    }
+    
+    /// Put the city list back as it was
+    ///
+    /// Groups keep whether they are collapsed now, as folding is not an edit. Cities that come back are
+    /// fetched again and data of cities that are gone is dropped.
+    fn restore(&mut self, snapshot: ListSnapshot) {
+        let collapsed: Vec<String> = self.groups
+            .iter()
+            .filter(|group| group.collapsed)
+            .map(|group| group.name.clone())
+            .collect();
+        let renamed = std::mem::replace(&mut self.cities, snapshot.cities);
+        self.follow_renames(&renamed);
+        self.groups = snapshot.groups;
+        for group in &mut self.groups {
+            group.collapsed = collapsed.contains(&group.name);
+        }
+        self.selected_city = snapshot.selected_city.min(self.cities.len().saturating_sub(1));
+        self.selected_group = snapshot.selected_group.filter(|&group| group < self.groups.len());
+        self.select_visible();
+        self.config_changed = true;
+        
+        let keys: Vec<String> = self.cities.iter().map(|city| city.key()).collect();
+        self.weather_states.retain(|key, _| keys.contains(key));
+        self.city_weather.retain(|key, _| keys.contains(key));
+        self.next_refresh.retain(|key, _| keys.contains(key));
+        for city in self.cities.clone() {
+            if *self.weather_state(&city) == WeatherState::NotLoaded {
+                self.request_city_fetch(&city, false);
+            }
+        }
+    }
+    
+    /// Show a message in the status line for a few seconds
+    pub fn show_toast(&mut self, message: String) {
+        self.toast = Some((message, Instant::now()));
+    }
+    
+    /// Units to display values in
+    pub fn units(&self) -> Units {
+        self.unit_settings.resolve()
+    }
+    
+    /// UI strings in the configured language
+    pub fn messages(&self) -> &'static Messages {
+        self.locale.messages()
+    }
+    
+    /// Switch between metric and imperial units
+    pub fn toggle_units(&mut self) {
+        self.unit_settings.toggle_system();
+        self.config_changed = true;
+    }
+    
+    /// The loaded settings with the cities, selection, units and refresh policy as they are now
+    pub fn current_config(&self) -> Config {
+        Config {
+            cities: self.cities.clone(),
+            groups: self.groups.clone(),
+            selected_city: self.selected_city,
+            units: self.unit_settings.clone(),
+            refresh: self.refresh.clone(),
+            ..self.config.clone()
+        }
+    }
+    
+    /// Write the current settings to the config file, if there is one
+    pub fn save_config(&mut self) {
+        self.config_changed = false;
+        self.selection_changed = false;
+        let Some(path) = &self.config_path else {
+            return;
+        };
+        let config = self.current_config();
+        match config.save_to(path) {
+            Ok(()) => {
+                self.config = config;
+                self.config_error = None;
+            }
+            Err(e) => self.config_error = Some(e.to_string()),
+        }
+    }
+    
+    /// Open the first-run wizard, starting from the loaded settings
+    pub fn start_setup(&mut self) {
+        self.setup = Setup {
+            provider: self.config.provider,
+            units: self.unit_settings.system,
+            language: self.locale.language,
+            ..Setup::default()
+        };
+        self.exit_edit_mode();
+        self.input_mode = InputMode::Setup;
+    }
+    
+    /// Highlight the next (`delta` 1) or previous (-1) option of the current wizard page
+    pub fn setup_cycle(&mut self, delta: isize) {
+        if self.setup.step == SetupStep::HomeCity {
+            if delta > 0 {
+                self.next_candidate();
+            } else {
+                self.previous_candidate();
+            }
+            return;
+        }
+        self.setup.cycle_option(delta);
+        self.locale.language = self.setup.language;
+    }
+    
+    /// Confirm the current wizard page
+    ///
+    /// The API key is checked and the home city looked up before moving on.
+    pub fn setup_confirm(&mut self) {
+        match self.setup.step {
+            SetupStep::ApiKey if self.input.trim().is_empty() => {
+                self.input_status = Some(self.messages().setup_key_missing.to_string());
+            }
+            SetupStep::ApiKey => {
+                self.input_status = Some(self.messages().setup_checking_key.to_string());
+                self.setup.key_check_requested = true;
+            }
+            SetupStep::HomeCity => {
+                if let Some(place) = self.candidates.get(self.selected_candidate).cloned() {
+                    self.finish_setup(Location::from(place));
+                } else if let Some(query) = LocationQuery::parse(&self.input) {
+                    self.finish_setup(Location::from_query(query));
+                } else if !self.input.trim().is_empty() {
+                    self.input_status = Some(self.messages().searching.to_string());
+                    self.geocode_requested = true;
+                }
+            }
+            _ => self.next_setup_step(),
+        }
+    }
+    
+    /// Go back one wizard page; leaves the app from the first one
+    pub fn setup_back(&mut self) {
+        self.input_status = None;
+        if !self.candidates.is_empty() {
+            self.candidates.clear();
+        } else if self.setup.previous_step() {
+            self.enter_setup_step();
+        } else {
+            self.running = false;
+        }
+    }
+    
+    /// Start checking the typed API key with a request to the service
+    ///
+    /// The outcome comes back as [`Event::KeyChecked`] through `sender`.
+    pub fn check_setup_key(&mut self, sender: &mpsc::UnboundedSender<Event>) {
+        let key = self.input.trim().to_string();
+        let provider = self.setup.provider.build(&key, self.setup.language, &self.config.endpoints, self.http.clone());
+        setup::spawn_key_check(provider, key, sender.clone());
+        self.setup.key_check_requested = false;
+    }
+    
+    /// Move on if `key` works, unless the wizard has left the key page or the key was changed meanwhile
+    pub fn key_checked(&mut self, key: String, result: Result<(), WeatherError>) {
+        if self.input_mode != InputMode::Setup || self.setup.step != SetupStep::ApiKey || self.input.trim() != key {
+            return;
+        }
+        match result {
+            Ok(()) => {
+                self.setup.save_key = key != self.api_key;
+                self.api_key = key;
+                self.next_setup_step();
+            }
+            Err(e) => {
+                self.input_status = Some(format!("{} - {}", e.message(self.messages()), e.suggestion(self.messages())));
+            }
+        }
+    }
+    
+    fn next_setup_step(&mut self) {
+        self.input_status = None;
+        if self.setup.next_step() {
+            self.enter_setup_step();
+        }
+    }
+    
+    /// Prepare the input for the wizard page just entered
+    fn enter_setup_step(&mut self) {
+        self.candidates.clear();
+        match self.setup.step {
+            SetupStep::ApiKey => self.input = self.api_key.clone(),
+            SetupStep::HomeCity => {
+                self.input.clear();
+                // The home city is looked up with the chosen service
+                self.provider = self.setup.provider.build(
+                    &self.api_key, self.setup.language, &self.config.endpoints, self.http.clone(),
+                );
+            }
+            _ => {}
+        }
+    }
+    
+    /// Write the settings chosen in the wizard and start showing the weather of the home city
+    fn finish_setup(&mut self, home: Location) {
+        let mut api_key = ApiKeySettings::default();
+        let mut key_error = None;
+        if self.setup.provider.needs_api_key() && self.setup.save_key {
+            // Kept next to the config, which only points at it
+            if let Some(path) = &self.config_path {
+                let file = path.with_file_name("api_key");
+                match write_key_file(&file, &self.api_key) {
+                    Ok(()) => api_key.file = Some(file),
+                    Err(e) => key_error = Some(e.to_string()),
+                }
+            }
+        }
+        let config = Config {
+            provider: self.setup.provider,
+            api_key,
+            units: UnitSettings { system: self.setup.units, ..UnitSettings::default() },
+            locale: LocaleSettings { language: self.setup.language, clock: None },
+            selected_city: 0,
+            cities: vec![home],
+            ..self.config.clone()
+        };
+        self.unit_settings = config.units.clone();
+        self.locale = config.locale.clone();
+        self.cities = config.cities.clone();
+        self.selected_city = 0;
+        self.config = config;
+        self.exit_edit_mode();
+        self.save_config();
+        if key_error.is_some() {
+            self.config_error = key_error;
+        }
+        self.load_weather();
+    }
+    
     /// Handle terminal resize events
     pub fn handle_resize(&mut self, width: u16, height: u16) {
         self.terminal_size = Some((width, height));
     }
     
     /// Handle tick events
+    ///
+    /// Refreshes every city whose refresh interval has run out and hides an expired toast.
     pub fn tick(&mut self) {
-        // Update time-based logic
-        // Could implement periodic weather refresh here
+        self.ticks = self.ticks.wrapping_add(1);
+        let now = Instant::now();
+        if self.toast.as_ref().is_some_and(|(_, shown)| now.duration_since(*shown) >= TOAST_DURATION) {
+            self.toast = None;
+        }
+        let due: Vec<Location> = self.cities
+            .iter()
+            .filter(|city| self.next_refresh.get(&city.key()).is_some_and(|&at| at <= now))
+            .cloned()
+            .collect();
+        for city in due {
+            self.next_refresh.remove(&city.key());
+            self.request_city_fetch(&city, true);
+        }
+    }
+    
+    /// Time left until the selected city is refreshed automatically
+    pub fn refresh_countdown(&self) -> Option<Duration> {
+        let city = self.cities.get(self.selected_city)?;
+        let at = self.next_refresh.get(&city.key())?;
+        Some(at.saturating_duration_since(Instant::now()))
+    }
+    
+    /// Current frame of the loading spinner
+    pub fn spinner(&self) -> char {
+        SPINNER_FRAMES[(self.ticks % SPINNER_FRAMES.len() as u64) as usize]
+    }
+    
+    /// Fetch state of a city
+    pub fn weather_state(&self, location: &Location) -> &WeatherState {
+        static NOT_LOADED: WeatherState = WeatherState::NotLoaded;
+        self.weather_states.get(&location.key()).unwrap_or(&NOT_LOADED)
+    }
+    
+    /// Last data fetched for a city, if any
+    pub fn weather_for(&self, location: &Location) -> Option<&CityWeather> {
+        self.city_weather.get(&location.key())
+    }
+    
+    /// Last data fetched for the selected city, if any
+    pub fn selected_weather(&self) -> Option<&CityWeather> {
+        self.cities.get(self.selected_city).and_then(|city| self.weather_for(city))
+    }
+    
+    /// Time zone of the selected city; UTC until its weather is loaded
+    pub fn selected_timezone(&self) -> FixedOffset {
+        self.selected_weather().map_or_else(|| Utc.fix(), |data| data.weather.timezone())
+    }
+    
+    /// Alerts currently in force for a city, most severe first
+    pub fn active_alerts(&self, location: &Location) -> Vec<&WeatherAlert> {
+        let now = Utc::now();
+        let mut alerts: Vec<&WeatherAlert> = self.weather_for(location)
+            .map(|data| data.alerts.iter().filter(|alert| alert.is_active(now)).collect())
+            .unwrap_or_default();
+        alerts.sort_by_key(|alert| std::cmp::Reverse(alert.severity()));
+        alerts
+    }
+    
+    /// Alerts currently in force for the selected city, most severe first
+    pub fn selected_alerts(&self) -> Vec<&WeatherAlert> {
+        self.cities
+            .get(self.selected_city)
+            .map(|city| self.active_alerts(city))
+            .unwrap_or_default()
+    }
+    
+    /// Open the alert details popup, if the selected city has any alerts
+    pub fn open_alerts(&mut self) {
+        if !self.selected_alerts().is_empty() {
+            self.alert_scroll = 0;
+            self.input_mode = InputMode::Alerts;
+        }
+    }
+    
+    /// Close the alert details popup
+    pub fn close_alerts(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+    
+    /// Scroll the alert details down by one line
+    pub fn scroll_alerts_down(&mut self) {
+        self.alert_scroll = self.alert_scroll.saturating_add(1);
+    }
+    
+    /// Scroll the alert details up by one line
+    pub fn scroll_alerts_up(&mut self) {
+        self.alert_scroll = self.alert_scroll.saturating_sub(1);
     }
     
-    /// Fetch weather data for the selected city
-    pub async fn fetch_weather(&mut self) -> AppResult<()> {
+    /// Open the history view for the selected city and load the chosen day
+    pub fn open_history(&mut self) {
+        if !self.cities.is_empty() {
+            self.input_mode = InputMode::History;
+            self.request_history();
+        }
+    }
+    
+    /// Close the history view
+    pub fn close_history(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+    
+    /// Move the history date by `days`, never past yesterday
+    pub fn shift_history_date(&mut self, days: i64) {
+        let yesterday = Utc::now().date_naive() - Days::new(1);
+        let date = if days < 0 {
+            self.history_date.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            self.history_date.checked_add_days(Days::new(days as u64))
+        };
+        if let Some(date) = date.map(|date| date.min(yesterday)) {
+            if date != self.history_date {
+                self.history_date = date;
+                self.history = None;
+                self.history_state = WeatherState::NotLoaded;
+            }
+        }
+    }
+    
+    /// Request the history of the selected city on [`App::history_date`]
+    pub fn request_history(&mut self) {
+        self.history = None;
+        self.history_state = WeatherState::Loading;
+        self.history_requested = true;
+    }
+    
+    /// Start fetching the requested history in the background
+    pub fn fetch_history(&mut self, sender: &mpsc::UnboundedSender<Event>) {
         if let Some(city) = self.cities.get(self.selected_city).cloned() {
-            match crate::connection::get_data(city, &self.api_key).await {
-                Ok(weather) => {
-                    self.current_weather = Some(weather);
-                    Ok(())
-                },
-                Err(e) => Err(e),
+            self.fetcher().spawn_history(city, self.history_date, sender.clone());
+        }
+        self.history_requested = false;
+    }
+    
+    /// Store a fetched history, unless the view has moved on to another city or day
+    pub fn history_loaded(&mut self, key: String, date: NaiveDate, history: History) {
+        if self.is_current_history(&key, date) {
+            self.history = Some(history);
+            self.history_state = WeatherState::Loaded(Utc::now());
+        }
+    }
+    
+    /// Record a failed history fetch, unless the view has moved on to another city or day
+    pub fn history_failed(&mut self, key: String, date: NaiveDate, error: WeatherError) {
+        if self.is_current_history(&key, date) {
+            self.history_state = WeatherState::Failed(error);
+        }
+    }
+    
+    fn is_current_history(&self, key: &str, date: NaiveDate) -> bool {
+        date == self.history_date
+            && self.cities.get(self.selected_city).is_some_and(|city| city.key() == key)
+    }
+    
+    /// Mark a city for fetching on the next [`App::fetch_weather`]
+    ///
+    /// With `force`, fresh cache entries are skipped. Cities that are already being fetched are left alone.
+    fn request_city_fetch(&mut self, location: &Location, force: bool) {
+        if *self.weather_state(location) != WeatherState::Loading {
+            self.weather_states.insert(location.key(), WeatherState::Loading);
+            self.pending_fetches.push((location.clone(), force));
+            self.request_weather_fetch();
+        }
+    }
+    
+    /// Fetch the selected city unless it was already loaded or is loading
+    fn fetch_selected_if_needed(&mut self) {
+        if let Some(city) = self.cities.get(self.selected_city).cloned() {
+            if matches!(self.weather_state(&city), WeatherState::NotLoaded | WeatherState::Failed(_)) {
+                self.request_city_fetch(&city, false);
             }
-        } else {
-            Ok(()) // No city selected, nothing to do
+        }
+    }
+    
+    /// Fetcher sharing this app's provider, cache and offline flag
+    pub fn fetcher(&self) -> WeatherFetcher {
+        WeatherFetcher {
+            provider: self.provider.clone(),
+            cache: self.cache.clone(),
+            offline: self.offline,
+            force: false,
+        }
+    }
+    
+    /// Start a background fetch for every pending city
+    ///
+    /// Results come back as [`Event::WeatherLoaded`] / [`Event::WeatherFailed`] through `sender`;
+    /// at most [`MAX_CONCURRENT_FETCHES`] run at the same time.
+    pub fn fetch_weather(&mut self, sender: &mpsc::UnboundedSender<Event>) {
+        let fetcher = self.fetcher();
+        for (city, force) in std::mem::take(&mut self.pending_fetches) {
+            let fetcher = WeatherFetcher { force, ..fetcher.clone() };
+            fetcher.spawn(city, self.fetch_limit.clone(), sender.clone());
+        }
+        self.fetch_requested = false;
+    }
+    
+    /// Store the result of a background fetch
+    pub fn weather_loaded(&mut self, key: String, data: CityWeather) {
+        // The city may have been removed while it was fetched
+        if self.schedule_refresh(&key) {
+            self.request_icon(&data.weather.icon);
+            self.weather_states.insert(key.clone(), WeatherState::Loaded(Utc::now()));
+            self.city_weather.insert(key, data);
+        }
+    }
+    
+    /// Queue a condition icon for loading, unless it is known already or glyphs are used anyway
+    fn request_icon(&mut self, code: &str) {
+        if self.icon_protocol != GraphicsProtocol::Glyphs && !self.icons.contains_key(code) {
+            self.icons.insert(code.to_string(), None);
+            self.pending_icons.push(code.to_string());
+        }
+    }
+    
+    /// Start loading the queued icons in the background
+    pub fn fetch_icons(&mut self, sender: &mpsc::UnboundedSender<Event>) {
+        let dir = self.cache.as_ref().map(|cache| cache.icon_dir());
+        for code in std::mem::take(&mut self.pending_icons) {
+            icons::spawn_load(self.http.clone(), dir.clone(), code, self.offline, sender.clone());
+        }
+    }
+    
+    /// Store a loaded icon
+    pub fn icon_loaded(&mut self, code: String, icon: Arc<Icon>) {
+        self.icons.insert(code, Some(icon));
+    }
+    
+    /// The icon for a condition, if it has been loaded
+    pub fn icon(&self, code: &str) -> Option<&Arc<Icon>> {
+        self.icons.get(code).and_then(|icon| icon.as_ref())
+    }
+    
+    /// Record a failed background fetch so the UI can show it
+    pub fn weather_failed(&mut self, key: String, error: WeatherError) {
+        if self.schedule_refresh(&key) {
+            self.weather_states.insert(key, WeatherState::Failed(error));
+        }
+    }
+    
+    /// Schedule the next automatic refresh of a city that was just fetched
+    ///
+    /// Returns false if the city is no longer in the list.
+    fn schedule_refresh(&mut self, key: &str) -> bool {
+        let Some(city) = self.cities.iter().find(|city| city.key() == key) else {
+            return false;
+        };
+        match self.refresh.next_refresh(city, Instant::now()) {
+            Some(at) => self.next_refresh.insert(key.to_string(), at),
+            None => self.next_refresh.remove(key),
+        };
+        true
+    }
+    
+    /// Returns the last fetch error for the selected city, if any
+    pub fn selected_error(&self) -> Option<&WeatherError> {
+        match self.cities.get(self.selected_city).map(|city| self.weather_state(city)) {
+            Some(WeatherState::Failed(e)) => Some(e),
+            _ => None,
         }
     }
     
@@ -157,24 +1209,45 @@ impl App {
         self.fetch_requested = true;
     }
     
-    /// Refresh the weather data on demand
+    /// Load the weather of every city, reusing fresh cached data
+    pub fn load_weather(&mut self) {
+        for city in self.cities.clone() {
+            self.request_city_fetch(&city, false);
+        }
+    }
+    
+    /// Refresh the weather data of the selected city, bypassing the cache
+    pub fn refresh_selected(&mut self) {
+        if let Some(city) = self.cities.get(self.selected_city).cloned() {
+            self.request_city_fetch(&city, true);
+        }
+    }
+    
+    /// Refresh the weather data of every city, bypassing the cache
     pub fn refresh_weather(&mut self) {
-        self.request_weather_fetch();
+        for city in self.cities.clone() {
+            self.request_city_fetch(&city, true);
+        }
     }
     
-    /// Remove the selected city
+    /// Remove the selected city; nothing happens while a group header is selected
     pub fn remove_selected_city(&mut self) {
-        if !self.cities.is_empty() {
-            self.cities.remove(self.selected_city);
+        if !self.cities.is_empty() && self.selected_group.is_none() {
+            let before = self.snapshot();
+            let removed = self.cities.remove(self.selected_city);
+            let label = fill(self.messages().edit_removed, &[&removed.name]);
+            // The same place may be in the list twice
+            if !self.cities.iter().any(|city| city.key() == removed.key()) {
+                self.weather_states.remove(&removed.key());
+                self.city_weather.remove(&removed.key());
+                self.next_refresh.remove(&removed.key());
+            }
             if self.selected_city >= self.cities.len() && !self.cities.is_empty() {
                 self.selected_city = self.cities.len() - 1;
             }
-            // Update weather if we still have cities
-            if !self.cities.is_empty() {
-                self.request_weather_fetch();
-            } else {
-                self.current_weather = None;
-            }
+            self.arrange_cities();
+            self.commit_edit(before, label);
+            self.fetch_selected_if_needed();
         }
     }
 }
\ No newline at end of file
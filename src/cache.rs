@@ -0,0 +1,175 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::connection::Location;
+
+/// Cache settings, read from the `[cache]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheSettings {
+    /// Whether fetched data is kept on disk at all
+    pub enabled: bool,
+    /// Seconds current conditions are reused without asking the service again
+    pub current_ttl_secs: u64,
+    /// Seconds a forecast is reused without asking the service again
+    pub forecast_ttl_secs: u64,
+    /// Seconds air quality data is reused without asking the service again
+    pub air_quality_ttl_secs: u64,
+    /// Seconds weather alerts are reused without asking the service again
+    pub alerts_ttl_secs: u64,
+    /// Seconds the UV index and minutely precipitation are reused without asking the service again
+    pub nowcast_ttl_secs: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            current_ttl_secs: 10 * 60,
+            forecast_ttl_secs: 60 * 60,
+            air_quality_ttl_secs: 30 * 60,
+            alerts_ttl_secs: 10 * 60,
+            nowcast_ttl_secs: 5 * 60,
+        }
+    }
+}
+
+/// The kinds of data that are cached, each with its own TTL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    Current,
+    Forecast,
+    AirQuality,
+    Alerts,
+    Nowcast,
+}
+
+impl CacheKind {
+    fn file_suffix(self) -> &'static str {
+        match self {
+            CacheKind::Current => "current",
+            CacheKind::Forecast => "forecast",
+            CacheKind::AirQuality => "air",
+            CacheKind::Alerts => "alerts",
+            CacheKind::Nowcast => "nowcast",
+        }
+    }
+
+    fn ttl(self, settings: &CacheSettings) -> Duration {
+        let secs = match self {
+            CacheKind::Current => settings.current_ttl_secs,
+            CacheKind::Forecast => settings.forecast_ttl_secs,
+            CacheKind::AirQuality => settings.air_quality_ttl_secs,
+            CacheKind::Alerts => settings.alerts_ttl_secs,
+            CacheKind::Nowcast => settings.nowcast_ttl_secs,
+        };
+        Duration::seconds(secs as i64)
+    }
+}
+
+/// A cached value and when it was fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry<T> {
+    pub fetched_at: DateTime<Utc>,
+    pub data: T,
+}
+
+/// Fetched weather data kept as JSON files in the user's cache directory.
+#[derive(Debug, Clone)]
+pub struct WeatherCache {
+    dir: PathBuf,
+    settings: CacheSettings,
+}
+
+impl WeatherCache {
+    /// Default cache directory, e.g. `~/.cache/weather-cli`.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("weather-cli"))
+    }
+
+    /// Opens the cache in the default directory; `None` if caching is disabled or there is no cache dir.
+    pub fn open(settings: &CacheSettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        Self::default_dir().map(|dir| Self::in_dir(dir, settings))
+    }
+
+    /// Opens the cache in the given directory.
+    pub fn in_dir(dir: impl Into<PathBuf>, settings: &CacheSettings) -> Self {
+        Self { dir: dir.into(), settings: settings.clone() }
+    }
+
+    /// Directory condition icons are kept in.
+    pub fn icon_dir(&self) -> PathBuf {
+        self.dir.join("icons")
+    }
+
+    /// Whether an entry of the given kind is still within its TTL.
+    pub fn is_fresh<T>(&self, kind: CacheKind, entry: &CacheEntry<T>) -> bool {
+        Utc::now() - entry.fetched_at < kind.ttl(&self.settings)
+    }
+
+    /// Reads the last stored entry, however old it is.
+    pub fn load<T: DeserializeOwned>(&self, kind: CacheKind, provider: &str, location: &Location) -> Option<CacheEntry<T>> {
+        let contents = fs::read_to_string(self.path(kind, provider, location)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Stores a freshly fetched value.
+    pub fn store<T: Serialize>(&self, kind: CacheKind, provider: &str, location: &Location, data: &T) -> io::Result<()> {
+        let entry = CacheEntry { fetched_at: Utc::now(), data };
+        let json = serde_json::to_string(&entry)?;
+        fs::create_dir_all(&self.dir)?;
+        write_atomically(&self.path(kind, provider, location), &json)
+    }
+
+    /// File for one location, provider and kind of data.
+    fn path(&self, kind: CacheKind, provider: &str, location: &Location) -> PathBuf {
+        let key = fnv1a(format!("{}\n{}", provider, location.key()).as_bytes());
+        self.dir.join(format!("{:016x}-{}.json", key, kind.file_suffix()))
+    }
+}
+
+/// Writes to a temporary file next to `path` and renames it into place,
+/// so readers never see a half-written file.
+///
+/// The new file keeps the permissions of the one it replaces, so a config made private stays private.
+pub(crate) fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    replace_file(path, contents.as_ref(), false)
+}
+
+/// Like [`write_atomically`], but the new file is only readable by the current user.
+pub(crate) fn write_private_atomically(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    replace_file(path, contents.as_ref(), true)
+}
+
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn replace_file(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    // Left over from a crash, possibly with other permissions; the mode only applies to new files
+    let _ = fs::remove_file(&tmp);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        if private {
+            options.mode(0o600);
+        } else if let Ok(metadata) = fs::metadata(path) {
+            options.mode(metadata.permissions().mode() & 0o777);
+        }
+    }
+    options.open(&tmp)?.write_all(contents)?;
+    fs::rename(&tmp, path)
+}
+
+/// FNV-1a hash; unlike `DefaultHasher` it is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
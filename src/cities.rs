@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use crate::connection::Location;
+
+/// A named section of the city list, read from `[[groups]]` in the config.
+///
+/// ```toml
+/// [[groups]]
+/// name = "Offices"
+/// collapsed = true
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CityGroup {
+    pub name: String,
+    /// Whether its cities are hidden in the list
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+impl CityGroup {
+    /// An expanded group.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self { name: name.into(), collapsed: false }
+    }
+}
+
+/// One line of the city list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CityRow {
+    /// Header of the group at this index of the group list
+    Group(usize),
+    /// The city at this index of the city list
+    City(usize),
+}
+
+/// Position of a city's section in the list: pinned cities first, then ungrouped ones, then each group in order.
+fn section(city: &Location, groups: &[CityGroup]) -> usize {
+    if city.pinned {
+        return 0;
+    }
+    city.group
+        .as_ref()
+        .and_then(|name| groups.iter().position(|group| &group.name == name))
+        .map_or(1, |index| index + 2)
+}
+
+/// Puts `cities` into list order, keeping their order within each section, and tidies up `groups`:
+/// groups only named by a city are added, groups without cities are dropped.
+///
+/// Returns the new index of the city that was at `selected`.
+pub fn arrange(cities: &mut Vec<Location>, groups: &mut Vec<CityGroup>, selected: usize) -> usize {
+    for city in cities.iter() {
+        if let Some(name) = &city.group {
+            if !groups.iter().any(|group| &group.name == name) {
+                groups.push(CityGroup::named(name.clone()));
+            }
+        }
+    }
+    groups.retain(|group| cities.iter().any(|city| city.group.as_ref() == Some(&group.name)));
+
+    let mut indexed: Vec<(usize, Location)> = std::mem::take(cities).into_iter().enumerate().collect();
+    indexed.sort_by_key(|(_, city)| section(city, groups));
+    let selected = indexed.iter().position(|&(index, _)| index == selected).unwrap_or(0);
+    *cities = indexed.into_iter().map(|(_, city)| city).collect();
+    selected
+}
+
+/// The lines of the list: pinned and ungrouped cities, then each group header followed by its cities unless collapsed.
+///
+/// Expects `cities` in the order [`arrange`] leaves them.
+pub fn rows(cities: &[Location], groups: &[CityGroup]) -> Vec<CityRow> {
+    let mut rows: Vec<CityRow> = (0..cities.len())
+        .filter(|&index| section(&cities[index], groups) < 2)
+        .map(CityRow::City)
+        .collect();
+    for (group_index, group) in groups.iter().enumerate() {
+        rows.push(CityRow::Group(group_index));
+        if !group.collapsed {
+            rows.extend(
+                (0..cities.len())
+                    .filter(|&index| section(&cities[index], groups) == group_index + 2)
+                    .map(CityRow::City),
+            );
+        }
+    }
+    rows
+}
+
+/// Moves the city at `index` so it becomes line `target` of the list, e.g. while it is dragged.
+///
+/// The city joins the section it lands in: dropped right below a group header it becomes the first
+/// city of that group, below another city it takes that city's group and pin, and above the first
+/// header it leaves its group. Returns its new index.
+pub fn move_to_row(cities: &mut Vec<Location>, groups: &mut Vec<CityGroup>, index: usize, target: usize) -> usize {
+    let others: Vec<CityRow> = rows(cities, groups)
+        .into_iter()
+        .filter(|&row| row != CityRow::City(index))
+        .collect();
+    let target = target.min(others.len());
+    // Below the line above, or at the very top in the section of the first line
+    let anchor = match target.checked_sub(1) {
+        Some(above) => Some((others[above], true)),
+        None => others.first().map(|&row| (row, false)),
+    };
+
+    let mut city = cities.remove(index);
+    let insert_at = match anchor {
+        Some((CityRow::City(other), after)) => {
+            let other = if other > index { other - 1 } else { other };
+            city.pinned = cities[other].pinned;
+            city.group = cities[other].group.clone();
+            if after { other + 1 } else { other }
+        }
+        Some((CityRow::Group(group), after)) => {
+            // Above the first header means outside of every group
+            city.pinned = false;
+            city.group = after.then(|| groups[group].name.clone());
+            0
+        }
+        None => 0,
+    };
+    cities.insert(insert_at, city);
+    arrange(cities, groups, insert_at)
+}
+
+/// Most edits kept for undo.
+pub const MAX_UNDO: usize = 100;
+
+/// The city list as edits change it, kept in the undo history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListSnapshot {
+    pub cities: Vec<Location>,
+    pub groups: Vec<CityGroup>,
+    pub selected_city: usize,
+    pub selected_group: Option<usize>,
+}
+
+/// An edit of the city list: the list as it was on the other side of it, and what it did.
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    snapshot: ListSnapshot,
+    label: String,
+}
+
+/// Undo and redo stacks of city list edits.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl EditHistory {
+    /// Records an edit given the list before it and a description such as "Removed Tokyo".
+    ///
+    /// Anything undone so far can no longer be redone; the oldest edits are forgotten past [`MAX_UNDO`].
+    pub fn record(&mut self, before: ListSnapshot, label: String) {
+        self.undo.push(Edit { snapshot: before, label });
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Takes back the last edit: returns the list to restore and the edit's description.
+    pub fn undo(&mut self, current: ListSnapshot) -> Option<(ListSnapshot, String)> {
+        let edit = self.undo.pop()?;
+        self.redo.push(Edit { snapshot: current, label: edit.label.clone() });
+        Some((edit.snapshot, edit.label))
+    }
+
+    /// Applies the last undone edit again: returns the list to restore and the edit's description.
+    pub fn redo(&mut self, current: ListSnapshot) -> Option<(ListSnapshot, String)> {
+        let edit = self.redo.pop()?;
+        self.undo.push(Edit { snapshot: current, label: edit.label.clone() });
+        Some((edit.snapshot, edit.label))
+    }
+}
@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+use clap::Parser;
+
+/// Terminal weather dashboard.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Show the last cached data instead of contacting the weather service
+    #[arg(long)]
+    pub offline: bool,
+    /// Read and save settings at this path instead of the user's config directory
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// OpenWeather API key; takes precedence over the environment and the config
+    #[arg(long, value_name = "KEY")]
+    pub api_key: Option<String>,
+}
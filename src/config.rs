@@ -0,0 +1,117 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::AppResult;
+use crate::cache::{write_atomically, write_private_atomically, CacheSettings};
+use crate::cities::CityGroup;
+use crate::connection::{Endpoints, HttpSettings, Location, ProviderKind};
+use crate::i18n::LocaleSettings;
+use crate::icons::IconSettings;
+use crate::refresh::RefreshSettings;
+use crate::secrets::{check_config_file, ApiKeySettings};
+use crate::units::UnitSettings;
+
+/// Cities shown until the user picks their own.
+const DEFAULT_CITIES: [&str; 10] = [
+    "Bucharest",
+    "London",
+    "New York",
+    "Budapest",
+    "Tokyo",
+    "Paris",
+    "Berlin",
+    "Moscow",
+    "Sydney",
+    "Toronto",
+];
+
+/// Settings read from `config.toml` in the user's config directory.
+///
+/// The city list and units are written back whenever they change in the app, the selection when it quits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Weather service to read from
+    pub provider: ProviderKind,
+    /// Where the API key comes from
+    pub api_key: ApiKeySettings,
+    /// Timeouts and retry policy for all requests
+    pub http: HttpSettings,
+    /// Base URLs of the weather services
+    pub endpoints: Endpoints,
+    /// How long fetched data is reused
+    pub cache: CacheSettings,
+    /// Units values are displayed in
+    pub units: UnitSettings,
+    /// Language and clock format
+    pub locale: LocaleSettings,
+    /// Automatic refresh policy
+    pub refresh: RefreshSettings,
+    /// How condition icons are drawn
+    pub icons: IconSettings,
+    /// Index of the selected city
+    pub selected_city: usize,
+    /// Tracked cities, in list order
+    pub cities: Vec<Location>,
+    /// Named sections of the city list, in list order
+    pub groups: Vec<CityGroup>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            provider: ProviderKind::default(),
+            api_key: ApiKeySettings::default(),
+            http: HttpSettings::default(),
+            endpoints: Endpoints::default(),
+            cache: CacheSettings::default(),
+            units: UnitSettings::default(),
+            locale: LocaleSettings::default(),
+            refresh: RefreshSettings::default(),
+            icons: IconSettings::default(),
+            selected_city: 0,
+            cities: DEFAULT_CITIES.into_iter().map(Location::named).collect(),
+            groups: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Default location of the config file, e.g. `~/.config/weather-cli/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("weather-cli").join("config.toml"))
+    }
+
+    /// Loads the config from `path`; a missing file yields the defaults.
+    ///
+    /// A config holding the API key must not be readable by other users.
+    pub fn load_from(path: &Path) -> AppResult<Self> {
+        let config: Self = match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| format!("invalid config file {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        check_config_file(path, &config.api_key)?;
+        Ok(config)
+    }
+
+    /// Writes the config to `path`, creating its directory if needed.
+    ///
+    /// The file is replaced atomically, so a crash never leaves a truncated config behind.
+    pub fn save_to(&self, path: &Path) -> AppResult<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| format!("cannot serialize config: {}", e))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // A key kept in the config makes it as secret as a key file
+        if self.api_key.value.is_some() {
+            write_private_atomically(path, contents)?;
+        } else {
+            write_atomically(path, contents)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use super::{error_from_status, WeatherError, WeatherResult, USER_AGENT};
+
+/// Timeouts and retry policy for the shared HTTP client, read from the `[http]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    /// Seconds allowed to establish a connection
+    pub connect_timeout_secs: u64,
+    /// Seconds allowed between two reads of the response
+    pub read_timeout_secs: u64,
+    /// Retries after the first attempt for transient failures
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following one
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the exponential backoff
+    pub max_backoff_ms: u64,
+    /// Longest `Retry-After` we are willing to wait for; longer ones fail right away
+    pub max_retry_after_secs: u64,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 5,
+            read_timeout_secs: 10,
+            max_retries: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 8_000,
+            max_retry_after_secs: 30,
+        }
+    }
+}
+
+/// What the network layer is doing about failing requests, for the status line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkStatus {
+    /// Waiting before the next attempt.
+    Retrying {
+        host: String,
+        attempt: u32,
+        max_retries: u32,
+        until: Instant,
+        reason: String,
+    },
+    /// The last request only went through after retrying.
+    Recovered { host: String, retries: u32 },
+    /// The last request failed even after retrying.
+    GaveUp { host: String, retries: u32, reason: String },
+}
+
+impl NetworkStatus {
+    /// Host the status is about.
+    pub fn host(&self) -> &str {
+        match self {
+            NetworkStatus::Retrying { host, .. }
+            | NetworkStatus::Recovered { host, .. }
+            | NetworkStatus::GaveUp { host, .. } => host,
+        }
+    }
+
+    /// How much the status line should prefer this status over others: ongoing retries first.
+    fn urgency(&self) -> u8 {
+        match self {
+            NetworkStatus::Retrying { .. } => 2,
+            NetworkStatus::GaveUp { .. } => 1,
+            NetworkStatus::Recovered { .. } => 0,
+        }
+    }
+}
+
+/// Noteworthy retry state of each request made through a client, keyed by request number.
+///
+/// Requests run concurrently, so each one only replaces its own entry.
+#[derive(Debug, Default)]
+struct StatusBoard {
+    next_request: u64,
+    by_request: HashMap<u64, NetworkStatus>,
+}
+
+/// Long-lived HTTP client shared by all providers.
+///
+/// Cloning is cheap; clones share the connection pool and the [`NetworkStatus`].
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    settings: HttpSettings,
+    status: Arc<Mutex<StatusBoard>>,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(&HttpSettings::default())
+    }
+}
+
+/// A failed attempt and the `Retry-After` the server asked for, if any.
+type AttemptError = (WeatherError, Option<Duration>);
+
+impl HttpClient {
+    /// Constructs a new instance of [`HttpClient`].
+    pub fn new(settings: &HttpSettings) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(Duration::from_secs(settings.connect_timeout_secs))
+            .read_timeout(Duration::from_secs(settings.read_timeout_secs))
+            .build()
+            // Only fails if the TLS backend cannot be set up, which `Client::new` would hit as well
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            settings: settings.clone(),
+            status: Arc::new(Mutex::new(StatusBoard::default())),
+        }
+    }
+
+    /// Current retry / backoff state, if anything noteworthy happened.
+    ///
+    /// With several requests in trouble, a running retry wins over a failure and that over a recovery;
+    /// among equals the latest request is shown.
+    pub fn status(&self) -> Option<NetworkStatus> {
+        let board = self.status.lock().ok()?;
+        board.by_request
+            .iter()
+            .max_by_key(|(&request, status)| (status.urgency(), request))
+            .map(|(_, status)| status.clone())
+    }
+
+    /// Numbers a new request for [`HttpClient::set_status`].
+    fn begin_request(&self) -> u64 {
+        self.status.lock().map_or(0, |mut board| {
+            board.next_request += 1;
+            board.next_request
+        })
+    }
+
+    /// Replaces the status of one request.
+    fn set_status(&self, request: u64, status: NetworkStatus) {
+        if let Ok(mut board) = self.status.lock() {
+            board.by_request.insert(request, status);
+        }
+    }
+
+    /// Records that `request` went through after `retries` retries.
+    ///
+    /// The host works again, so earlier failures and recoveries on it are cleared;
+    /// other requests still retrying there keep their status.
+    fn succeeded(&self, request: u64, host: String, retries: u32) {
+        if let Ok(mut board) = self.status.lock() {
+            board.by_request.retain(|&other, status| {
+                other != request && (status.host() != host || matches!(status, NetworkStatus::Retrying { .. }))
+            });
+            if retries > 0 {
+                board.by_request.insert(request, NetworkStatus::Recovered { host, retries });
+            }
+        }
+    }
+
+    /// Performs a GET request and returns the body as text, retrying transient failures.
+    ///
+    /// `lookup` is the place the URL looks up by name, postal code or ID, reported as
+    /// [`WeatherError::CityNotFound`] when the service does not know it; `None` for any other request.
+    pub async fn get_text(&self, url: Url, lookup: Option<&str>) -> WeatherResult<String> {
+        let body = self.get_bytes(url, lookup).await?;
+        String::from_utf8(body).map_err(|e| WeatherError::Parse(e.to_string()))
+    }
+
+    /// Performs a GET request and returns the raw body, retrying transient failures.
+    pub async fn get_bytes(&self, url: Url, lookup: Option<&str>) -> WeatherResult<Vec<u8>> {
+        let host = url.host_str().unwrap_or_default().to_string();
+        let request = self.begin_request();
+        let mut retries = 0;
+        loop {
+            let (error, retry_after) = match self.attempt(url.clone(), lookup).await {
+                Ok(body) => {
+                    self.succeeded(request, host, retries);
+                    return Ok(body);
+                }
+                Err(failure) => failure,
+            };
+
+            let delay = match retry_after {
+                Some(wait) if wait > Duration::from_secs(self.settings.max_retry_after_secs) => None,
+                Some(wait) => Some(wait),
+                None => Some(self.backoff(retries)),
+            };
+            let delay = delay.filter(|_| error.is_transient() && retries < self.settings.max_retries);
+            let Some(delay) = delay else {
+                if retries > 0 {
+                    self.set_status(request, NetworkStatus::GaveUp { host, retries, reason: error.to_string() });
+                }
+                return Err(error);
+            };
+
+            retries += 1;
+            self.set_status(request, NetworkStatus::Retrying {
+                host: host.clone(),
+                attempt: retries,
+                max_retries: self.settings.max_retries,
+                until: Instant::now() + delay,
+                reason: error.to_string(),
+            });
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// A single request, without retries.
+    async fn attempt(&self, url: Url, lookup: Option<&str>) -> Result<Vec<u8>, AttemptError> {
+        // Make the asynchronous request
+        let response = self.client.get(url).send().await.map_err(|e| (e.into(), None))?;
+
+        // Check that the status is OK
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = (status == StatusCode::TOO_MANY_REQUESTS)
+                .then(|| response.headers().get(RETRY_AFTER))
+                .flatten()
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            let error_text = response.text().await.map_err(|e| (e.into(), None))?;
+            return Err((error_from_status(status, &error_text, lookup), retry_after));
+        }
+
+        response.bytes().await.map(|body| body.to_vec()).map_err(|e| (e.into(), None))
+    }
+
+    /// Exponential backoff before retry number `retries + 1`.
+    fn backoff(&self, retries: u32) -> Duration {
+        let delay = self.settings.initial_backoff_ms.saturating_mul(1 << retries.min(16));
+        Duration::from_millis(delay.min(self.settings.max_backoff_ms))
+    }
+}
+
+/// Parses a `Retry-After` header, given either in seconds or as an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    let wait = date.signed_duration_since(chrono::Utc::now()).num_seconds();
+    Some(Duration::from_secs(wait.max(0) as u64))
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use super::{open_meteo, Endpoints, GeoLocation, HttpClient, WeatherError, WeatherResult};
+
+/// How a location is identified when asking a weather service about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum LocationQuery {
+    /// Free-text name, e.g. "Paris" or "Paris,FR"
+    Name { name: String },
+    /// Coordinates in decimal degrees
+    Coordinates { lat: f64, lon: f64 },
+    /// Postal code plus ISO 3166 country code
+    Zip { zip: String, country: String },
+    /// Provider city ID (OpenWeather's city list)
+    CityId { id: u64 },
+}
+
+impl LocationQuery {
+    /// Parses the non-name forms typed in the Add City popup:
+    /// `lat,lon`, `zip:<code>,<country>` and `id:<city id>`.
+    ///
+    /// Returns `None` for anything else, which should be treated as a name.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Some(id) = input.strip_prefix("id:") {
+            return id.trim().parse().ok().map(|id| LocationQuery::CityId { id });
+        }
+        if let Some(zip) = input.strip_prefix("zip:") {
+            let (zip, country) = zip.split_once(',')?;
+            return Some(LocationQuery::Zip {
+                zip: zip.trim().to_string(),
+                country: country.trim().to_uppercase(),
+            });
+        }
+        let (lat, lon) = input.split_once(',')?;
+        let lat: f64 = lat.trim().parse().ok()?;
+        let lon: f64 = lon.trim().parse().ok()?;
+        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+            Some(LocationQuery::Coordinates { lat, lon })
+        } else {
+            None
+        }
+    }
+
+    /// Short human readable form, used as display name for locations added by query.
+    pub fn describe(&self) -> String {
+        match self {
+            LocationQuery::Name { name } => name.clone(),
+            LocationQuery::Coordinates { lat, lon } => format!("{:.4}, {:.4}", lat, lon),
+            LocationQuery::Zip { zip, country } => format!("{} {}", zip, country),
+            LocationQuery::CityId { id } => format!("City #{}", id),
+        }
+    }
+}
+
+/// A tracked location: the name shown in the list and how to look it up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub name: String,
+    pub country: Option<String>,      // ISO 3166 country code, when known
+    pub query: LocationQuery,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,                 // Kept at the top of the list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,        // Name of the list group it is filed under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,     // IANA time zone from the geocoder, for services that report none
+}
+
+impl Location {
+    /// A location looked up by name.
+    pub fn named(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            query: LocationQuery::Name { name: name.clone() },
+            name,
+            country: None,
+            pinned: false,
+            group: None,
+            timezone: None,
+        }
+    }
+
+    /// A location looked up by the given query, named after it.
+    pub fn from_query(query: LocationQuery) -> Self {
+        Self { name: query.describe(), country: None, query, pinned: false, group: None, timezone: None }
+    }
+
+    /// Identifies the place looked up, independent of the name shown.
+    pub fn key(&self) -> String {
+        serde_json::to_string(&self.query).unwrap_or_default()
+    }
+
+    /// Label for the city list, e.g. "Paris, FR".
+    pub fn label(&self) -> String {
+        match &self.country {
+            Some(country) => format!("{}, {}", self.name, country),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The coordinates to ask coordinate based services about, geocoding the location if needed.
+    pub(crate) async fn place(&self, http: &HttpClient, endpoints: &Endpoints) -> WeatherResult<GeoLocation> {
+        match &self.query {
+            LocationQuery::Coordinates { lat, lon } => Ok(GeoLocation {
+                name: self.name.clone(),
+                state: None,
+                country: self.country.clone().unwrap_or_default(),
+                lat: *lat,
+                lon: *lon,
+                timezone: self.timezone.clone(),
+            }),
+            LocationQuery::Name { name } => open_meteo::resolve(http, endpoints, name, None).await,
+            LocationQuery::Zip { zip, country } => open_meteo::resolve(http, endpoints, zip, Some(country)).await,
+            LocationQuery::CityId { .. } => Err(WeatherError::Unsupported(
+                "city IDs only work with OpenWeather".to_string(),
+            )),
+        }
+    }
+}
+
+impl From<GeoLocation> for Location {
+    fn from(place: GeoLocation) -> Self {
+        Self {
+            name: place.name,
+            country: Some(place.country),
+            query: LocationQuery::Coordinates { lat: place.lat, lon: place.lon },
+            pinned: false,
+            group: None,
+            timezone: place.timezone,
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use serde::Deserialize;
+use crate::i18n::Language;
+use super::{
+    apparent_temperature, endpoint, open_meteo, parse_json, CityInfo, Endpoints, Forecast, ForecastEntry,
+    GeoLocation, History, HttpClient, Location, MinutePrecipitation, Nowcast, WeatherError, WeatherProvider,
+    WeatherResult,
+};
+
+/// Default MET Norway API host.
+pub(crate) const BASE_URL: &str = "https://api.met.no";
+
+/// Raw `/weatherapi/locationforecast/2.0/complete` payload.
+#[derive(Debug, Deserialize)]
+pub struct LocationForecast {
+    pub properties: Properties,
+}
+
+/// `properties` block of the location forecast.
+#[derive(Debug, Deserialize)]
+pub struct Properties {
+    pub timeseries: Vec<TimeStep>,
+}
+
+/// One step of the time series (hourly at first, 6-hourly later on).
+#[derive(Debug, Deserialize)]
+pub struct TimeStep {
+    pub time: DateTime<Utc>,
+    pub data: StepData,
+}
+
+/// Instant values plus summaries for the following periods.
+#[derive(Debug, Deserialize)]
+pub struct StepData {
+    pub instant: Instant,
+    pub next_1_hours: Option<Period>,
+    pub next_6_hours: Option<Period>,
+}
+
+/// `instant` block of a time step.
+#[derive(Debug, Deserialize)]
+pub struct Instant {
+    pub details: InstantDetails,
+}
+
+/// Values measured at the time step.
+#[derive(Debug, Deserialize)]
+pub struct InstantDetails {
+    pub air_temperature: f64,
+    pub air_pressure_at_sea_level: f64,
+    pub relative_humidity: f64,
+    pub wind_speed: f64,
+    pub wind_from_direction: f64,
+    pub cloud_area_fraction: f64,
+    pub wind_speed_of_gust: Option<f64>,
+    pub ultraviolet_index_clear_sky: Option<f64>,
+}
+
+/// Summary of the period following a time step.
+#[derive(Debug, Deserialize)]
+pub struct Period {
+    pub summary: Summary,
+    pub details: Option<PeriodDetails>,
+}
+
+/// Symbol describing the period, e.g. `lightrainshowers_day`.
+#[derive(Debug, Deserialize)]
+pub struct Summary {
+    pub symbol_code: String,
+}
+
+/// Accumulated values over the period.
+#[derive(Debug, Deserialize)]
+pub struct PeriodDetails {
+    pub precipitation_amount: Option<f64>,
+    pub probability_of_precipitation: Option<f64>,
+}
+
+/// Raw `/weatherapi/nowcast/2.0/complete` payload (Nordic countries only).
+#[derive(Debug, Deserialize)]
+pub struct NowcastForecast {
+    pub properties: NowcastProperties,
+}
+
+/// `properties` block of the nowcast.
+#[derive(Debug, Deserialize)]
+pub struct NowcastProperties {
+    pub timeseries: Vec<NowcastStep>,
+}
+
+/// One 5-minute step of the nowcast (hourly after the first two hours).
+#[derive(Debug, Deserialize)]
+pub struct NowcastStep {
+    pub time: DateTime<Utc>,
+    pub data: NowcastData,
+}
+
+/// Instant values of a nowcast step.
+#[derive(Debug, Deserialize)]
+pub struct NowcastData {
+    pub instant: NowcastInstant,
+}
+
+/// `instant` block of a nowcast step.
+#[derive(Debug, Deserialize)]
+pub struct NowcastInstant {
+    pub details: NowcastDetails,
+}
+
+/// Values measured or predicted at the nowcast step.
+#[derive(Debug, Deserialize)]
+pub struct NowcastDetails {
+    pub precipitation_rate: Option<f64>,   // mm/h; missing outside radar coverage
+}
+
+impl StepData {
+    /// The shortest period summary available for this step.
+    fn period(&self) -> Option<&Period> {
+        self.next_1_hours.as_ref().or(self.next_6_hours.as_ref())
+    }
+}
+
+/// Maps a MET Norway symbol code to OpenWeather's (main, description, icon).
+pub(crate) fn symbol_condition(symbol: &str) -> (&'static str, String, String) {
+    let (base, variant) = symbol.split_once('_').unwrap_or((symbol, "day"));
+    let suffix = if variant == "night" { 'n' } else { 'd' };
+    let (intensity, kind) = if let Some(rest) = base.strip_prefix("light") {
+        ("light ", rest)
+    } else if let Some(rest) = base.strip_prefix("heavy") {
+        ("heavy ", rest)
+    } else {
+        ("", base)
+    };
+    let (main, description, icon) = if kind.contains("thunder") {
+        ("Thunderstorm", format!("{}{}", intensity, "thunderstorm"), "11")
+    } else if kind.starts_with("sleet") {
+        ("Snow", format!("{}{}", intensity, "sleet"), "13")
+    } else if kind.starts_with("snow") {
+        ("Snow", format!("{}{}", intensity, "snow"), "13")
+    } else if kind.starts_with("rainshowers") {
+        ("Rain", format!("{}{}", intensity, "rain showers"), "09")
+    } else if kind.starts_with("rain") {
+        ("Rain", format!("{}{}", intensity, "rain"), "10")
+    } else {
+        match kind {
+            "clearsky" => ("Clear", "clear sky".to_string(), "01"),
+            "fair" => ("Clouds", "fair".to_string(), "02"),
+            "partlycloudy" => ("Clouds", "partly cloudy".to_string(), "03"),
+            "cloudy" => ("Clouds", "cloudy".to_string(), "04"),
+            "fog" => ("Fog", "fog".to_string(), "50"),
+            _ => ("Unknown", kind.to_string(), "01"),
+        }
+    };
+    (main, description, format!("{}{}", icon, suffix))
+}
+
+/// Parses a location forecast into current conditions; name, country and time zone come from the geocoder.
+///
+/// MET Norway has no observations, so the first time step stands in for "now"
+/// and the min/max are taken over the next 24 hours.
+pub fn parse_current(body: &str, place: &GeoLocation) -> WeatherResult<CityInfo> {
+    let data: LocationForecast = parse_json(body)?;
+    let series = data.properties.timeseries;
+    let first = series.first()
+        .ok_or_else(|| WeatherError::Parse("location forecast contains no time steps".to_string()))?;
+    let now = &first.data.instant.details;
+    let next_day = series.iter()
+        .take_while(|step| step.time <= first.time + chrono::Duration::hours(24))
+        .map(|step| step.data.instant.details.air_temperature);
+    let temp_min = next_day.clone().fold(f64::INFINITY, f64::min);
+    let temp_max = next_day.fold(f64::NEG_INFINITY, f64::max);
+    let period = first.data.period()
+        .ok_or_else(|| WeatherError::Parse("first time step has no period summary".to_string()))?;
+    let (weather_main, description, icon) = symbol_condition(&period.summary.symbol_code);
+
+    Ok(CityInfo {
+        name: place.name.clone(),
+        country: place.country.clone(),
+        lat: place.lat,
+        lon: place.lon,
+
+        temperature: now.air_temperature,
+        feels_like: apparent_temperature(now.air_temperature, now.relative_humidity, now.wind_speed),
+        temp_min,
+        temp_max,
+
+        weather_main: weather_main.to_string(),
+        description,
+        icon,
+
+        humidity: now.relative_humidity.round() as u8,
+        pressure: now.air_pressure_at_sea_level.round() as u32,
+        wind_speed: now.wind_speed,
+        wind_direction: Some(now.wind_from_direction.round() as u16),
+        wind_gust: now.wind_speed_of_gust,
+
+        visibility: None,
+        clouds: now.cloud_area_fraction.round() as u8,
+        // Expected over the next hour rather than measured over the last one
+        rain_1h: first.data.next_1_hours.as_ref()
+            .and_then(|p| p.details.as_ref())
+            .and_then(|d| d.precipitation_amount),
+        snow_1h: None,
+
+        sunrise: None,
+        sunset: None,
+        hourly_temps: None,
+
+        // locationforecast has no time zone information, the geocoder does
+        utc_offset: place.utc_offset(&first.time),
+
+        timestamp: first.time,
+    })
+}
+
+/// Parses a location forecast, keeping the steps that fall on a 3-hour boundary.
+pub fn parse_forecast(body: &str, place: &GeoLocation) -> WeatherResult<Forecast> {
+    let data: LocationForecast = parse_json(body)?;
+    let entries = data.properties.timeseries.into_iter()
+        .filter(|step| step.time.hour() % 3 == 0)
+        .filter_map(|step| {
+            let period = step.data.period()?;
+            let (weather_main, description, icon) = symbol_condition(&period.summary.symbol_code);
+            let probability = period.details.as_ref()
+                .and_then(|d| d.probability_of_precipitation)
+                .unwrap_or(0.0);
+            Some(ForecastEntry {
+                time: step.time,
+                temperature: step.data.instant.details.air_temperature,
+                precipitation_probability: probability / 100.0,
+                wind_speed: step.data.instant.details.wind_speed,
+                weather_main: weather_main.to_string(),
+                description,
+                icon,
+            })
+        })
+        .collect();
+
+    Ok(Forecast { city: place.name.clone(), entries })
+}
+
+/// Parses a nowcast into minutely precipitation, repeating each 5-minute rate for its minutes.
+pub fn parse_minutely(body: &str) -> WeatherResult<Vec<MinutePrecipitation>> {
+    let data: NowcastForecast = parse_json(body)?;
+    let steps = data.properties.timeseries;
+    let mut minutely = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        let Some(rate) = step.data.instant.details.precipitation_rate else { continue };
+        let length = steps.get(i + 1)
+            .map_or(5, |next| (next.time - step.time).num_minutes())
+            .clamp(1, 5);
+        minutely.extend((0..length).map(|minute| MinutePrecipitation {
+            time: step.time + Duration::minutes(minute),
+            intensity: rate,
+        }));
+    }
+    Ok(minutely)
+}
+
+/// MET Norway backend (free, no API key; places are resolved through Open-Meteo).
+///
+/// Conditions come as symbol codes and are described in English; only place names are localized.
+#[derive(Debug, Clone, Default)]
+pub struct MetNorway {
+    http: HttpClient,
+    language: Language,
+    endpoints: Endpoints,
+}
+
+impl MetNorway {
+    /// Constructs a new instance of [`MetNorway`].
+    pub fn new(http: HttpClient) -> Self {
+        Self { http, language: Language::default(), endpoints: Endpoints::default() }
+    }
+
+    /// Sets the language geocoding results are named in.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Sets the base URLs requests are sent to (geocoding goes to Open-Meteo).
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Fetches a raw product (`locationforecast` or `nowcast`) for the given coordinates.
+    async fn product(&self, product: &str, lat: f64, lon: f64) -> WeatherResult<String> {
+        // met.no asks clients to send at most four decimals
+        let params = [("lat", format!("{:.4}", lat)), ("lon", format!("{:.4}", lon))];
+        let url = endpoint(&self.endpoints.met_no, &format!("/weatherapi/{}/2.0/complete", product), &params)?;
+        self.http.get_text(url, None).await
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for MetNorway {
+    fn name(&self) -> &'static str {
+        "MET Norway"
+    }
+
+    async fn current(&self, location: &Location) -> WeatherResult<CityInfo> {
+        let place = location.place(&self.http, &self.endpoints).await?;
+        let body = self.product("locationforecast", place.lat, place.lon).await?;
+        parse_current(&body, &place)
+    }
+
+    async fn forecast(&self, location: &Location) -> WeatherResult<Forecast> {
+        let place = location.place(&self.http, &self.endpoints).await?;
+        let body = self.product("locationforecast", place.lat, place.lon).await?;
+        parse_forecast(&body, &place)
+    }
+
+    async fn nowcast(&self, lat: f64, lon: f64) -> WeatherResult<Nowcast> {
+        let (forecast, nowcast) = tokio::join!(
+            self.product("locationforecast", lat, lon),
+            self.product("nowcast", lat, lon),
+        );
+        let forecast: LocationForecast = parse_json(&forecast?)?;
+        let uv_index = forecast.properties.timeseries.first()
+            .and_then(|step| step.data.instant.details.ultraviolet_index_clear_sky);
+        // The nowcast only covers the Nordic countries; elsewhere there is just the UV index
+        let minutely = match nowcast {
+            Ok(body) => parse_minutely(&body)?,
+            Err(e) if e.is_transient() => return Err(e),
+            Err(_) => Vec::new(),
+        };
+        Ok(Nowcast { uv_index, minutely })
+    }
+
+    async fn history(&self, location: &Location, start: NaiveDate, end: NaiveDate) -> WeatherResult<History> {
+        // locationforecast has no past data
+        open_meteo::history(&self.http, &self.endpoints, location, start, end).await
+    }
+
+    async fn geocode(&self, query: &str) -> WeatherResult<Vec<GeoLocation>> {
+        open_meteo::geocode(&self.http, &self.endpoints, query, self.language).await
+    }
+}
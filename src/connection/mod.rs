@@ -0,0 +1,590 @@
+use std::fmt;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, DurationRound, FixedOffset, NaiveDate, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use crate::i18n::{fill, Language, Messages};
+use crate::secrets::redact_url;
+
+pub mod client;
+pub mod location;
+pub mod met_no;
+pub mod open_meteo;
+pub mod openweather;
+
+pub use client::{HttpClient, HttpSettings, NetworkStatus};
+pub use location::{Location, LocationQuery};
+pub use met_no::MetNorway;
+pub use open_meteo::OpenMeteo;
+pub use openweather::{get_air_quality, get_alerts, get_data, get_data_from, get_forecast, OpenWeather};
+
+/// User agent sent with every request (MET Norway rejects anonymous clients).
+pub const USER_AGENT: &str = concat!("weather-cli/", env!("CARGO_PKG_VERSION"));
+
+/// Result type for everything that talks to a weather service.
+pub type WeatherResult<T> = Result<T, WeatherError>;
+
+/// Reasons a weather fetch can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeatherError {
+    /// The service does not know the requested city.
+    CityNotFound(String),
+    /// The API key was rejected.
+    InvalidApiKey,
+    /// Too many requests were made with this key.
+    RateLimited,
+    /// The service could not be reached at all.
+    NetworkUnreachable(String),
+    /// The service did not answer in time.
+    Timeout,
+    /// The response did not have the expected shape.
+    Parse(String),
+    /// The selected service cannot handle this kind of request.
+    Unsupported(String),
+    /// Running offline and nothing is cached for this request.
+    Offline,
+    /// Any other non-success answer from the service.
+    Api { status: u16, message: String },
+}
+
+impl WeatherError {
+    /// Whether the failure is temporary, i.e. retrying later might help.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WeatherError::Timeout | WeatherError::NetworkUnreachable(_) | WeatherError::RateLimited => true,
+            WeatherError::Api { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// The error as shown to the user, in the language of `messages`.
+    pub fn message(&self, messages: &Messages) -> String {
+        match self {
+            WeatherError::CityNotFound(city) => fill(messages.error_city_not_found, &[city]),
+            WeatherError::InvalidApiKey => messages.error_invalid_api_key.to_string(),
+            WeatherError::RateLimited => messages.error_rate_limited.to_string(),
+            WeatherError::NetworkUnreachable(reason) => fill(messages.error_network_unreachable, &[reason]),
+            WeatherError::Timeout => messages.error_timeout.to_string(),
+            WeatherError::Parse(reason) => fill(messages.error_parse, &[reason]),
+            WeatherError::Unsupported(reason) => fill(messages.error_unsupported, &[reason]),
+            WeatherError::Offline => messages.error_offline.to_string(),
+            WeatherError::Api { status, message } => fill(messages.error_api, &[status, message]),
+        }
+    }
+
+    /// A short hint telling the user how to get rid of the error, in the language of `messages`.
+    pub fn suggestion(&self, messages: &Messages) -> &'static str {
+        match self {
+            WeatherError::CityNotFound(_) => messages.suggest_city_not_found,
+            WeatherError::InvalidApiKey => messages.suggest_invalid_api_key,
+            WeatherError::RateLimited => messages.suggest_rate_limited,
+            WeatherError::NetworkUnreachable(_) => messages.suggest_network_unreachable,
+            WeatherError::Timeout => messages.suggest_timeout,
+            WeatherError::Parse(_) => messages.suggest_parse,
+            WeatherError::Unsupported(_) => messages.suggest_unsupported,
+            WeatherError::Offline => messages.suggest_offline,
+            WeatherError::Api { .. } => messages.suggest_api,
+        }
+    }
+}
+
+impl fmt::Display for WeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Language::English.messages()))
+    }
+}
+
+impl std::error::Error for WeatherError {}
+
+impl From<reqwest::Error> for WeatherError {
+    fn from(err: reqwest::Error) -> Self {
+        // reqwest puts the request URL, API key included, into its messages
+        let err = match err.url().map(redact_url) {
+            Some(url) => err.with_url(url),
+            None => err,
+        };
+        if err.is_timeout() {
+            WeatherError::Timeout
+        } else if err.is_decode() {
+            WeatherError::Parse(err.to_string())
+        } else {
+            WeatherError::NetworkUnreachable(err.to_string())
+        }
+    }
+}
+
+/// Error body sent along with non-success statuses
+/// (`message` for OpenWeather, `reason` for Open-Meteo).
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    #[serde(alias = "reason")]
+    message: String,
+}
+
+/// Maps a non-success response to a [`WeatherError`].
+///
+/// A 404 only means the city is unknown when the request looked up `lookup`; on endpoints addressed
+/// by coordinates or code it is a plain API error.
+fn error_from_status(status: reqwest::StatusCode, body: &str, lookup: Option<&str>) -> WeatherError {
+    match (status.as_u16(), lookup) {
+        (401, _) => WeatherError::InvalidApiKey,
+        (404, Some(city)) => WeatherError::CityNotFound(city.to_string()),
+        (429, _) => WeatherError::RateLimited,
+        (code, _) => WeatherError::Api {
+            status: code,
+            message: serde_json::from_str::<ApiErrorBody>(body)
+                .map(|b| b.message)
+                .unwrap_or_else(|_| body.to_string()),
+        },
+    }
+}
+
+/// Detailed weather information for a city, including extra data for graphs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CityInfo {
+    // Basic city information
+    pub name: String,
+    pub country: String,
+    pub lat: f64,              // Latitude of the observation point
+    pub lon: f64,              // Longitude of the observation point
+    
+    // Current weather
+    pub temperature: f64,      // current temperature in Celsius
+    pub feels_like: f64,       // "feels like" temperature
+    pub temp_min: f64,         // minimum temperature
+    pub temp_max: f64,         // maximum temperature
+    
+    // Weather description
+    pub weather_main: String,  // Short description (e.g., "Clear", "Rain")
+    pub description: String,   // Detailed description
+    pub icon: String,          // Icon ID for weather condition
+    
+    // Additional data
+    pub humidity: u8,          // Humidity percentage
+    pub pressure: u32,         // Atmospheric pressure in hPa
+    pub wind_speed: f64,       // Wind speed in m/s
+    pub wind_direction: Option<u16>, // Wind direction in degrees, if reported
+    pub wind_gust: Option<f64>, // Wind gust in m/s, if reported
+    pub visibility: Option<u32>, // Visibility in meters, if reported
+    pub clouds: u8,            // Cloudiness in percentages
+    pub rain_1h: Option<f64>,  // Rain volume for the last hour in mm
+    pub snow_1h: Option<f64>,  // Snow volume for the last hour in mm
+    
+    // Extra detailed data for graphs
+    pub sunrise: Option<DateTime<Utc>>,  // Sunrise time (UTC)
+    pub sunset: Option<DateTime<Utc>>,   // Sunset time (UTC)
+    
+    // Upcoming temperatures from the forecast (for drawing graphs)
+    pub hourly_temps: Option<Vec<f64>>,
+    
+    // Local time of the city
+    #[serde(default)]
+    pub utc_offset: Option<i32>,   // Offset from UTC in seconds, if the service reports it
+    
+    // Timestamp when the data was calculated
+    pub timestamp: DateTime<Utc>,
+}
+
+impl CityInfo {
+    /// The city's time zone; UTC if the service does not report one.
+    pub fn timezone(&self) -> FixedOffset {
+        self.utc_offset.and_then(FixedOffset::east_opt).unwrap_or_else(|| Utc.fix())
+    }
+
+    /// A timestamp in the city's local time.
+    pub fn local_time(&self, time: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        time.with_timezone(&self.timezone())
+    }
+
+    /// The time zone as shown next to times, e.g. "UTC+09:00", or just "UTC" if unknown.
+    pub fn timezone_label(&self) -> String {
+        match self.utc_offset {
+            Some(_) => format!("UTC{}", self.timezone()),
+            None => "UTC".to_string(),
+        }
+    }
+}
+
+/// One timestamped step of a forecast.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ForecastEntry {
+    pub time: DateTime<Utc>,         // Start of the forecast window (UTC)
+    pub temperature: f64,            // Temperature in Celsius
+    pub precipitation_probability: f64, // Probability of precipitation, 0.0 - 1.0
+    pub wind_speed: f64,             // Wind speed in m/s
+    pub weather_main: String,        // Short description (e.g., "Clear", "Rain")
+    pub description: String,         // Detailed description
+    pub icon: String,                // Icon ID for weather condition
+}
+
+/// Forecast for a city, entries sorted by time.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Forecast {
+    pub city: String,
+    pub entries: Vec<ForecastEntry>,
+}
+
+impl Forecast {
+    /// Temperatures of the first `count` entries, for graphs.
+    pub fn temperatures(&self, count: usize) -> Vec<f64> {
+        self.entries.iter().take(count).map(|e| e.temperature).collect()
+    }
+}
+
+/// Pollutant concentrations in μg/m³.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PollutantComponents {
+    pub pm2_5: f64,
+    pub pm10: f64,
+    pub o3: f64,
+    pub no2: f64,
+    pub so2: f64,
+    pub co: f64,
+}
+
+impl PollutantComponents {
+    /// Each pollutant with its concentration and its 1 (good) - 5 (very poor) level,
+    /// using the bands of OpenWeather's air quality index.
+    pub fn levels(&self) -> [(&'static str, f64, u8); 6] {
+        fn level(value: f64, bands: [f64; 4]) -> u8 {
+            bands.iter().position(|&limit| value < limit).map_or(5, |i| i as u8 + 1)
+        }
+        [
+            ("PM2.5", self.pm2_5, level(self.pm2_5, [10.0, 25.0, 50.0, 75.0])),
+            ("PM10", self.pm10, level(self.pm10, [20.0, 50.0, 100.0, 200.0])),
+            ("O3", self.o3, level(self.o3, [60.0, 100.0, 140.0, 180.0])),
+            ("NO2", self.no2, level(self.no2, [40.0, 70.0, 150.0, 200.0])),
+            ("SO2", self.so2, level(self.so2, [20.0, 80.0, 250.0, 350.0])),
+            ("CO", self.co, level(self.co, [4400.0, 9400.0, 12400.0, 15400.0])),
+        ]
+    }
+}
+
+/// Air quality at one point in time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AirQualityReading {
+    pub time: DateTime<Utc>,
+    pub aqi: u8,               // 1 (good) - 5 (very poor)
+    pub components: PollutantComponents,
+}
+
+/// Current air quality plus the hourly outlook, when the service has one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AirQuality {
+    pub current: AirQualityReading,
+    pub forecast: Vec<AirQualityReading>,
+}
+
+/// Name of an air quality index level.
+pub fn aqi_label(aqi: u8) -> &'static str {
+    match aqi {
+        1 => "Good",
+        2 => "Fair",
+        3 => "Moderate",
+        4 => "Poor",
+        _ => "Very Poor",
+    }
+}
+
+/// An hourly observation from the weather archive.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Observation {
+    pub time: DateTime<Utc>,
+    pub temperature: f64,            // Temperature in Celsius
+    pub humidity: u8,                // Relative humidity in %
+    pub precipitation: f64,          // Rain and snow over the hour, in mm
+    pub wind_speed: f64,             // Wind speed in m/s
+    pub weather_main: String,        // Short description (e.g., "Clear", "Rain")
+    pub description: String,         // Detailed description
+}
+
+/// Past weather for a city, observations sorted by time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct History {
+    pub city: String,
+    pub utc_offset: i32,           // Offset of the city's local time from UTC in seconds
+    pub observations: Vec<Observation>,
+}
+
+impl History {
+    /// A timestamp in the city's local time.
+    pub fn local_time(&self, time: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        time.with_timezone(&FixedOffset::east_opt(self.utc_offset).unwrap_or_else(|| Utc.fix()))
+    }
+}
+
+/// How serious a weather alert is, as far as its name tells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertSeverity {
+    Advisory,
+    Watch,
+    Warning,
+}
+
+/// A government weather warning (storm, heat, flood, ...).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WeatherAlert {
+    pub sender: String,            // Issuing agency
+    pub event: String,             // e.g. "Severe Thunderstorm Warning"
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub description: String,
+    pub tags: Vec<String>,         // e.g. "Wind", "Flood"
+}
+
+impl WeatherAlert {
+    /// Whether the alert applies at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Severity guessed from the event name; services do not report one.
+    pub fn severity(&self) -> AlertSeverity {
+        let event = self.event.to_lowercase();
+        if ["warning", "red", "extreme", "emergency"].iter().any(|word| event.contains(word)) {
+            AlertSeverity::Warning
+        } else if ["watch", "orange", "amber"].iter().any(|word| event.contains(word)) {
+            AlertSeverity::Watch
+        } else {
+            AlertSeverity::Advisory
+        }
+    }
+}
+
+/// Precipitation expected during one minute.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MinutePrecipitation {
+    pub time: DateTime<Utc>,       // Start of the minute
+    pub intensity: f64,            // Precipitation rate in mm/h
+}
+
+/// Whether it rains within the next hour, and when that changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RainOutlook {
+    /// No rain for the whole hour.
+    Dry,
+    /// Dry now, rain after this many minutes.
+    StartsIn(i64),
+    /// Raining now, dry after this many minutes.
+    StopsIn(i64),
+    /// Rain for the whole hour.
+    Ongoing,
+}
+
+/// UV index and minute-by-minute precipitation for the next hour.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Nowcast {
+    pub uv_index: Option<f64>,     // Current UV index, if the service reports it
+    pub minutely: Vec<MinutePrecipitation>, // Sorted by time; empty outside the service's coverage
+}
+
+impl Nowcast {
+    /// Rates below this many mm/h do not count as rain.
+    pub const RAIN_THRESHOLD: f64 = 0.1;
+
+    /// Precipitation rate for each of the 60 minutes starting at `now`; `None` where there is no data.
+    pub fn next_hour(&self, now: DateTime<Utc>) -> [Option<f64>; 60] {
+        // Entries start on whole minutes, the one covering `now` up to a minute earlier
+        let start = now.duration_trunc(chrono::Duration::minutes(1)).unwrap_or(now);
+        let mut minutes = [None; 60];
+        for entry in &self.minutely {
+            let offset = (entry.time - start).num_minutes();
+            if let Some(slot) = usize::try_from(offset).ok().and_then(|i| minutes.get_mut(i)) {
+                *slot = Some(entry.intensity);
+            }
+        }
+        minutes
+    }
+
+    /// When rain starts or stops within the hour following `now`; `None` without minutely data.
+    pub fn rain_outlook(&self, now: DateTime<Utc>) -> Option<RainOutlook> {
+        let raining: Vec<bool> = self.next_hour(now).iter()
+            .map_while(|minute| minute.map(|intensity| intensity >= Self::RAIN_THRESHOLD))
+            .collect();
+        let (&now_raining, later) = raining.split_first()?;
+        let change = later.iter().position(|&wet| wet != now_raining).map(|i| i as i64 + 1);
+        Some(match (now_raining, change) {
+            (false, None) => RainOutlook::Dry,
+            (false, Some(minutes)) => RainOutlook::StartsIn(minutes),
+            (true, Some(minutes)) => RainOutlook::StopsIn(minutes),
+            (true, None) => RainOutlook::Ongoing,
+        })
+    }
+}
+
+/// WHO exposure category of a UV index, 0 (low) - 4 (extreme).
+pub fn uv_category(uv_index: f64) -> usize {
+    match uv_index.round() as i64 {
+        i64::MIN..=2 => 0,
+        3..=5 => 1,
+        6..=7 => 2,
+        8..=10 => 3,
+        _ => 4,
+    }
+}
+
+/// A place returned by a geocoding lookup.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeoLocation {
+    pub name: String,
+    pub state: Option<String>,     // Region / state, when the service knows it
+    pub country: String,           // ISO 3166 country code
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default)]
+    pub timezone: Option<String>,  // IANA time zone, e.g. "Asia/Tokyo", when the service knows it
+}
+
+impl GeoLocation {
+    /// Offset of the place's local time from UTC at `time`, in seconds, if its time zone is known.
+    pub fn utc_offset(&self, time: &DateTime<Utc>) -> Option<i32> {
+        let zone: Tz = self.timezone.as_deref()?.parse().ok()?;
+        Some(zone.offset_from_utc_datetime(&time.naive_utc()).fix().local_minus_utc())
+    }
+
+    /// One-line description for candidate lists, e.g. "Paris, Île-de-France, FR (48.86, 2.35)".
+    pub fn describe(&self) -> String {
+        match &self.state {
+            Some(state) => format!("{}, {}, {} ({:.2}, {:.2})", self.name, state, self.country, self.lat, self.lon),
+            None => format!("{}, {} ({:.2}, {:.2})", self.name, self.country, self.lat, self.lon),
+        }
+    }
+}
+
+/// A weather service the dashboard can read from.
+///
+/// Every backend normalizes its payloads into [`CityInfo`] and [`Forecast`]
+/// (metric units, UTC timestamps, OpenWeather icon IDs).
+#[async_trait]
+pub trait WeatherProvider: fmt::Debug + Send + Sync {
+    /// Human readable name of the service.
+    fn name(&self) -> &'static str;
+
+    /// Current conditions for a location.
+    async fn current(&self, location: &Location) -> WeatherResult<CityInfo>;
+
+    /// Forecast for a location, in 3-hour steps.
+    async fn forecast(&self, location: &Location) -> WeatherResult<Forecast>;
+
+    /// Places matching a free-text query, best match first.
+    async fn geocode(&self, query: &str) -> WeatherResult<Vec<GeoLocation>>;
+
+    /// Air quality at the given coordinates.
+    async fn air_quality(&self, _lat: f64, _lon: f64) -> WeatherResult<AirQuality> {
+        Err(WeatherError::Unsupported(format!("{} has no air quality data", self.name())))
+    }
+
+    /// Hourly observations from `start` to `end` (inclusive, local dates of the location).
+    async fn history(&self, location: &Location, start: NaiveDate, end: NaiveDate) -> WeatherResult<History>;
+
+    /// Weather alerts issued for the given coordinates.
+    async fn alerts(&self, _lat: f64, _lon: f64) -> WeatherResult<Vec<WeatherAlert>> {
+        Err(WeatherError::Unsupported(format!("{} has no weather alerts", self.name())))
+    }
+
+    /// UV index and precipitation for the next hour at the given coordinates.
+    async fn nowcast(&self, _lat: f64, _lon: f64) -> WeatherResult<Nowcast> {
+        Err(WeatherError::Unsupported(format!("{} has no nowcast", self.name())))
+    }
+}
+
+/// Base URLs of the weather services, read from the `[endpoints]` config section.
+///
+/// Pointing them elsewhere is mainly useful for proxies and tests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Endpoints {
+    pub openweather: String,
+    pub open_meteo: String,
+    pub open_meteo_archive: String,
+    pub open_meteo_geocoding: String,
+    pub met_no: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            openweather: openweather::BASE_URL.to_string(),
+            open_meteo: open_meteo::BASE_URL.to_string(),
+            open_meteo_archive: open_meteo::ARCHIVE_URL.to_string(),
+            open_meteo_geocoding: open_meteo::GEOCODING_URL.to_string(),
+            met_no: met_no::BASE_URL.to_string(),
+        }
+    }
+}
+
+/// The weather services that can be selected in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProviderKind {
+    #[default]
+    OpenWeather,
+    OpenMeteo,
+    MetNorway,
+}
+
+impl ProviderKind {
+    /// Every supported service, in the order they are offered.
+    pub const ALL: [ProviderKind; 3] = [ProviderKind::OpenWeather, ProviderKind::OpenMeteo, ProviderKind::MetNorway];
+
+    /// Name of the service as shown to the user.
+    pub fn label(self) -> &'static str {
+        match self {
+            ProviderKind::OpenWeather => "OpenWeather",
+            ProviderKind::OpenMeteo => "Open-Meteo",
+            ProviderKind::MetNorway => "MET Norway",
+        }
+    }
+
+    /// Whether the service only answers with an API key.
+    pub fn needs_api_key(self) -> bool {
+        self == ProviderKind::OpenWeather
+    }
+
+    /// Builds the provider on top of the shared client;
+    /// the API key is only used by services that need one.
+    pub fn build(
+        self,
+        api_key: &str,
+        language: Language,
+        endpoints: &Endpoints,
+        http: HttpClient,
+    ) -> Arc<dyn WeatherProvider> {
+        let endpoints = endpoints.clone();
+        match self {
+            ProviderKind::OpenWeather => Arc::new(
+                OpenWeather::new(api_key, http).with_language(language).with_endpoints(endpoints),
+            ),
+            ProviderKind::OpenMeteo => Arc::new(
+                OpenMeteo::new(http).with_language(language).with_endpoints(endpoints),
+            ),
+            ProviderKind::MetNorway => Arc::new(
+                MetNorway::new(http).with_language(language).with_endpoints(endpoints),
+            ),
+        }
+    }
+}
+
+/// Builds a request URL, percent-encoding every query parameter.
+fn endpoint(base: &str, path: &str, params: &[(&str, String)]) -> WeatherResult<Url> {
+    Url::parse_with_params(&format!("{}{}", base, path), params)
+        .map_err(|e| WeatherError::NetworkUnreachable(format!("invalid URL {}{}: {}", base, path, e)))
+}
+
+/// Parses a JSON body, reporting shape mismatches as [`WeatherError::Parse`].
+fn parse_json<T: serde::de::DeserializeOwned>(body: &str) -> WeatherResult<T> {
+    serde_json::from_str(body).map_err(|e| WeatherError::Parse(e.to_string()))
+}
+
+/// Apparent temperature (Australian BoM formula) for services that do not report one.
+fn apparent_temperature(temp: f64, humidity: f64, wind_speed: f64) -> f64 {
+    let vapour_pressure = humidity / 100.0 * 6.105 * (17.27 * temp / (237.7 + temp)).exp();
+    temp + 0.33 * vapour_pressure - 0.70 * wind_speed - 4.00
+}
+
+/// Returns the URL for the weather condition icon.
+pub fn get_icon_url(icon_id: &str) -> String {
+    format!("https://openweathermap.org/img/wn/{}@2x.png", icon_id)
+}
\ No newline at end of file
@@ -0,0 +1,508 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use crate::i18n::Language;
+use super::{
+    endpoint, parse_json, CityInfo, Endpoints, Forecast, ForecastEntry, GeoLocation, History, Location,
+    HttpClient, MinutePrecipitation, Nowcast, Observation, WeatherError, WeatherProvider, WeatherResult,
+};
+
+/// Default Open-Meteo forecast API host.
+pub(crate) const BASE_URL: &str = "https://api.open-meteo.com";
+
+/// Default Open-Meteo historical weather API host.
+pub(crate) const ARCHIVE_URL: &str = "https://archive-api.open-meteo.com";
+
+/// The archive lags behind by a few days; more recent dates are served by the forecast API.
+const ARCHIVE_DELAY_DAYS: i64 = 5;
+
+/// Default Open-Meteo geocoding API host (also used by backends without their own geocoder).
+pub(crate) const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com";
+
+/// Raw `/v1/forecast` payload for the current conditions request.
+#[derive(Debug, Deserialize)]
+pub struct CurrentResponse {
+    pub utc_offset_seconds: i32,   // Of the local time zone, requested with `timezone=auto`
+    pub current: CurrentSection,
+    pub daily: DailySection,
+}
+
+/// `current` block, requested with the variables listed in [`CURRENT_VARIABLES`].
+#[derive(Debug, Deserialize)]
+pub struct CurrentSection {
+    pub time: i64,
+    pub temperature_2m: f64,
+    pub relative_humidity_2m: f64,
+    pub apparent_temperature: f64,
+    pub is_day: u8,
+    pub weather_code: u8,
+    pub cloud_cover: f64,
+    pub pressure_msl: f64,
+    pub wind_speed_10m: f64,
+    pub wind_direction_10m: f64,
+    pub wind_gusts_10m: Option<f64>,
+    pub rain: Option<f64>,         // mm over the preceding hour
+    pub snowfall: Option<f64>,     // cm over the preceding hour
+}
+
+/// `daily` block, one value per day.
+#[derive(Debug, Deserialize)]
+pub struct DailySection {
+    pub temperature_2m_max: Vec<f64>,
+    pub temperature_2m_min: Vec<f64>,
+    pub sunrise: Vec<i64>,
+    pub sunset: Vec<i64>,
+}
+
+/// Raw `/v1/forecast` payload for the hourly forecast request.
+#[derive(Debug, Deserialize)]
+pub struct HourlyResponse {
+    pub hourly: HourlySection,
+}
+
+/// `hourly` block, one array per variable.
+#[derive(Debug, Deserialize)]
+pub struct HourlySection {
+    pub time: Vec<i64>,
+    pub temperature_2m: Vec<f64>,
+    pub precipitation_probability: Vec<Option<f64>>,
+    pub wind_speed_10m: Vec<f64>,
+    pub weather_code: Vec<u8>,
+    pub is_day: Vec<u8>,
+}
+
+/// Raw `/v1/archive` payload (also returned by `/v1/forecast` for past dates).
+#[derive(Debug, Deserialize)]
+pub struct ArchiveResponse {
+    pub utc_offset_seconds: i32,   // Of the local time zone, requested with `timezone=auto`
+    pub hourly: ArchiveSection,
+}
+
+/// `hourly` block of the archive payload; values are missing for hours not yet observed.
+#[derive(Debug, Deserialize)]
+pub struct ArchiveSection {
+    pub time: Vec<i64>,
+    pub temperature_2m: Vec<Option<f64>>,
+    pub relative_humidity_2m: Vec<Option<f64>>,
+    pub precipitation: Vec<Option<f64>>,
+    pub wind_speed_10m: Vec<Option<f64>>,
+    pub weather_code: Vec<Option<u8>>,
+}
+
+/// Raw `/v1/forecast` payload for the nowcast request.
+#[derive(Debug, Deserialize)]
+pub struct NowcastResponse {
+    pub current: NowcastCurrent,
+    pub minutely_15: MinutelySection,
+}
+
+/// `current` block of the nowcast request.
+#[derive(Debug, Deserialize)]
+pub struct NowcastCurrent {
+    pub uv_index: Option<f64>,
+}
+
+/// `minutely_15` block; each value is the precipitation of the preceding 15 minutes, in mm.
+#[derive(Debug, Deserialize)]
+pub struct MinutelySection {
+    pub time: Vec<i64>,
+    pub precipitation: Vec<Option<f64>>,
+}
+
+/// Raw `/v1/search` geocoding payload; `results` is absent when nothing matched.
+#[derive(Debug, Deserialize)]
+pub struct GeocodingResponse {
+    #[serde(default)]
+    pub results: Vec<GeocodingItem>,
+}
+
+/// One geocoding match.
+#[derive(Debug, Deserialize)]
+pub struct GeocodingItem {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub country_code: String,
+    pub admin1: Option<String>,
+    pub timezone: Option<String>,
+}
+
+impl From<GeocodingItem> for GeoLocation {
+    fn from(item: GeocodingItem) -> Self {
+        GeoLocation {
+            name: item.name,
+            state: item.admin1,
+            country: item.country_code,
+            lat: item.latitude,
+            lon: item.longitude,
+            timezone: item.timezone,
+        }
+    }
+}
+
+/// Variables requested in the `current` block.
+const CURRENT_VARIABLES: &str = "temperature_2m,relative_humidity_2m,apparent_temperature,is_day,\
+    weather_code,cloud_cover,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,rain,snowfall";
+
+/// Maps a WMO weather code to OpenWeather's (main, description, icon prefix).
+pub(crate) fn wmo_condition(code: u8) -> (&'static str, &'static str, &'static str) {
+    match code {
+        0 => ("Clear", "clear sky", "01"),
+        1 => ("Clouds", "mainly clear", "02"),
+        2 => ("Clouds", "partly cloudy", "03"),
+        3 => ("Clouds", "overcast", "04"),
+        45 => ("Fog", "fog", "50"),
+        48 => ("Fog", "depositing rime fog", "50"),
+        51 => ("Drizzle", "light drizzle", "09"),
+        53 => ("Drizzle", "moderate drizzle", "09"),
+        55 => ("Drizzle", "dense drizzle", "09"),
+        56 | 57 => ("Drizzle", "freezing drizzle", "09"),
+        61 => ("Rain", "slight rain", "10"),
+        63 => ("Rain", "moderate rain", "10"),
+        65 => ("Rain", "heavy rain", "10"),
+        66 | 67 => ("Rain", "freezing rain", "13"),
+        71 => ("Snow", "slight snow fall", "13"),
+        73 => ("Snow", "moderate snow fall", "13"),
+        75 => ("Snow", "heavy snow fall", "13"),
+        77 => ("Snow", "snow grains", "13"),
+        80 => ("Rain", "slight rain showers", "09"),
+        81 => ("Rain", "moderate rain showers", "09"),
+        82 => ("Rain", "violent rain showers", "09"),
+        85 | 86 => ("Snow", "snow showers", "13"),
+        95 => ("Thunderstorm", "thunderstorm", "11"),
+        96 | 99 => ("Thunderstorm", "thunderstorm with hail", "11"),
+        _ => ("Unknown", "unknown conditions", "01"),
+    }
+}
+
+/// OpenWeather style icon ID for a WMO code.
+fn wmo_icon(code: u8, is_day: bool) -> String {
+    format!("{}{}", wmo_condition(code).2, if is_day { 'd' } else { 'n' })
+}
+
+/// Converts a unix timestamp, reporting bad values as a parse error.
+fn timestamp(ts: i64) -> WeatherResult<DateTime<Utc>> {
+    DateTime::from_timestamp(ts, 0)
+        .ok_or_else(|| WeatherError::Parse(format!("invalid timestamp {}", ts)))
+}
+
+/// Parses a current conditions body; name and country come from the geocoder.
+pub fn parse_current(body: &str, place: &GeoLocation) -> WeatherResult<CityInfo> {
+    let data: CurrentResponse = parse_json(body)?;
+    let current = data.current;
+    let (weather_main, description, _) = wmo_condition(current.weather_code);
+    let first_day = |values: &[f64], what: &str| {
+        values.first().copied()
+            .ok_or_else(|| WeatherError::Parse(format!("response contains no daily {}", what)))
+    };
+
+    Ok(CityInfo {
+        name: place.name.clone(),
+        country: place.country.clone(),
+        lat: place.lat,
+        lon: place.lon,
+
+        temperature: current.temperature_2m,
+        feels_like: current.apparent_temperature,
+        temp_min: first_day(&data.daily.temperature_2m_min, "minimum")?,
+        temp_max: first_day(&data.daily.temperature_2m_max, "maximum")?,
+
+        weather_main: weather_main.to_string(),
+        description: description.to_string(),
+        icon: wmo_icon(current.weather_code, current.is_day == 1),
+
+        humidity: current.relative_humidity_2m.round() as u8,
+        pressure: current.pressure_msl.round() as u32,
+        wind_speed: current.wind_speed_10m,
+        wind_direction: Some(current.wind_direction_10m.round() as u16),
+        wind_gust: current.wind_gusts_10m,
+
+        visibility: None,
+        clouds: current.cloud_cover.round() as u8,
+        rain_1h: current.rain,
+        snow_1h: current.snowfall.map(|cm| cm * 10.0),
+
+        sunrise: data.daily.sunrise.first().copied().map(timestamp).transpose()?,
+        sunset: data.daily.sunset.first().copied().map(timestamp).transpose()?,
+        hourly_temps: None,
+
+        utc_offset: Some(data.utc_offset_seconds),
+
+        timestamp: timestamp(current.time)?,
+    })
+}
+
+/// Parses an hourly forecast body, keeping every third hour from now on.
+pub fn parse_forecast(body: &str, place: &GeoLocation) -> WeatherResult<Forecast> {
+    let data: HourlyResponse = parse_json(body)?;
+    let hourly = data.hourly;
+    let len = hourly.time.len();
+    let lengths = [
+        hourly.temperature_2m.len(),
+        hourly.precipitation_probability.len(),
+        hourly.wind_speed_10m.len(),
+        hourly.weather_code.len(),
+        hourly.is_day.len(),
+    ];
+    if lengths.iter().any(|&l| l != len) {
+        return Err(WeatherError::Parse("hourly arrays differ in length".to_string()));
+    }
+    let now = Utc::now().timestamp() - 3600;
+
+    let entries = (0..len)
+        .filter(|&i| hourly.time[i] >= now)
+        .step_by(3)
+        .map(|i| {
+            let code = hourly.weather_code[i];
+            let (weather_main, description, _) = wmo_condition(code);
+            Ok(ForecastEntry {
+                time: timestamp(hourly.time[i])?,
+                temperature: hourly.temperature_2m[i],
+                precipitation_probability: hourly.precipitation_probability[i].unwrap_or(0.0) / 100.0,
+                wind_speed: hourly.wind_speed_10m[i],
+                weather_main: weather_main.to_string(),
+                description: description.to_string(),
+                icon: wmo_icon(code, hourly.is_day[i] == 1),
+            })
+        })
+        .collect::<WeatherResult<Vec<_>>>()?;
+
+    Ok(Forecast { city: place.name.clone(), entries })
+}
+
+/// Parses an archive body into its observations, skipping hours without data.
+pub fn parse_history(body: &str, place: &GeoLocation) -> WeatherResult<History> {
+    let data: ArchiveResponse = parse_json(body)?;
+    let hourly = data.hourly;
+    let len = hourly.time.len();
+    let lengths = [
+        hourly.temperature_2m.len(),
+        hourly.relative_humidity_2m.len(),
+        hourly.precipitation.len(),
+        hourly.wind_speed_10m.len(),
+        hourly.weather_code.len(),
+    ];
+    if lengths.iter().any(|&l| l != len) {
+        return Err(WeatherError::Parse("hourly arrays differ in length".to_string()));
+    }
+
+    let observations = (0..len)
+        .filter_map(|i| {
+            let temperature = hourly.temperature_2m[i]?;
+            let code = hourly.weather_code[i].unwrap_or(0);
+            let (weather_main, description, _) = wmo_condition(code);
+            Some(timestamp(hourly.time[i]).map(|time| Observation {
+                time,
+                temperature,
+                humidity: hourly.relative_humidity_2m[i].unwrap_or(0.0).round() as u8,
+                precipitation: hourly.precipitation[i].unwrap_or(0.0),
+                wind_speed: hourly.wind_speed_10m[i].unwrap_or(0.0),
+                weather_main: weather_main.to_string(),
+                description: description.to_string(),
+            }))
+        })
+        .collect::<WeatherResult<Vec<_>>>()?;
+
+    Ok(History { city: place.name.clone(), utc_offset: data.utc_offset_seconds, observations })
+}
+
+/// Parses a nowcast body, spreading each 15-minute sum evenly over its minutes.
+pub fn parse_nowcast(body: &str) -> WeatherResult<Nowcast> {
+    let data: NowcastResponse = parse_json(body)?;
+    let quarters = data.minutely_15;
+    if quarters.time.len() != quarters.precipitation.len() {
+        return Err(WeatherError::Parse("minutely arrays differ in length".to_string()));
+    }
+    let mut minutely = Vec::with_capacity(quarters.time.len() * 15);
+    for (&end, amount) in quarters.time.iter().zip(quarters.precipitation) {
+        let Some(amount) = amount else { continue };
+        for minute in (1..=15).rev() {
+            minutely.push(MinutePrecipitation {
+                time: timestamp(end - minute * 60)?,
+                intensity: amount * 4.0,
+            });
+        }
+    }
+    Ok(Nowcast { uv_index: data.current.uv_index, minutely })
+}
+
+/// Fetches hourly observations for a place from the Open-Meteo archive
+/// (also used by backends without their own history).
+pub(crate) async fn history(
+    http: &HttpClient,
+    endpoints: &Endpoints,
+    location: &Location,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> WeatherResult<History> {
+    if start > end {
+        return Err(WeatherError::Unsupported(format!("{} is after {}", start, end)));
+    }
+    let today = Utc::now().date_naive();
+    if end > today {
+        return Err(WeatherError::Unsupported(format!("{} is in the future", end)));
+    }
+    let place = location.place(http, endpoints).await?;
+    let mut params = coordinate_params(&place);
+    params.extend([
+        ("start_date", start.to_string()),
+        ("end_date", end.to_string()),
+        ("hourly", "temperature_2m,relative_humidity_2m,precipitation,wind_speed_10m,weather_code".to_string()),
+        ("wind_speed_unit", "ms".to_string()),
+        ("timeformat", "unixtime".to_string()),
+        ("timezone", "auto".to_string()),
+    ]);
+    let url = if end > today - Duration::days(ARCHIVE_DELAY_DAYS) {
+        endpoint(&endpoints.open_meteo, "/v1/forecast", &params)?
+    } else {
+        endpoint(&endpoints.open_meteo_archive, "/v1/archive", &params)?
+    };
+
+    let body = http.get_text(url, None).await?;
+    parse_history(&body, &place)
+}
+
+/// Looks up places by name or postal code with the Open-Meteo geocoding API,
+/// optionally restricted to one country.
+pub(crate) async fn geocode_in(
+    http: &HttpClient,
+    endpoints: &Endpoints,
+    query: &str,
+    country: Option<&str>,
+    language: Language,
+) -> WeatherResult<Vec<GeoLocation>> {
+    let mut params = vec![
+        ("name", query.to_string()),
+        ("count", "5".to_string()),
+        ("language", language.code().to_string()),
+        ("format", "json".to_string()),
+    ];
+    if let Some(country) = country {
+        params.push(("countryCode", country.to_string()));
+    }
+    let url = endpoint(&endpoints.open_meteo_geocoding, "/v1/search", &params)?;
+    let body = http.get_text(url, Some(query)).await?;
+    let data: GeocodingResponse = parse_json(&body)?;
+    Ok(data.results.into_iter().map(GeoLocation::from).collect())
+}
+
+/// Looks up places by name with the Open-Meteo geocoding API, naming them in `language`.
+pub(crate) async fn geocode(
+    http: &HttpClient,
+    endpoints: &Endpoints,
+    query: &str,
+    language: Language,
+) -> WeatherResult<Vec<GeoLocation>> {
+    geocode_in(http, endpoints, query, None, language).await
+}
+
+/// Resolves a name or postal code to its best geocoding match.
+pub(crate) async fn resolve(
+    http: &HttpClient,
+    endpoints: &Endpoints,
+    query: &str,
+    country: Option<&str>,
+) -> WeatherResult<GeoLocation> {
+    geocode_in(http, endpoints, query, country, Language::default()).await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| WeatherError::CityNotFound(query.to_string()))
+}
+
+/// Query parameters selecting a place in forecast requests.
+fn coordinate_params(place: &GeoLocation) -> Vec<(&'static str, String)> {
+    vec![("latitude", place.lat.to_string()), ("longitude", place.lon.to_string())]
+}
+
+/// Open-Meteo backend (free, no API key).
+///
+/// Conditions come as WMO codes and are described in English; only place names are localized.
+#[derive(Debug, Clone, Default)]
+pub struct OpenMeteo {
+    http: HttpClient,
+    language: Language,
+    endpoints: Endpoints,
+}
+
+impl OpenMeteo {
+    /// Constructs a new instance of [`OpenMeteo`].
+    pub fn new(http: HttpClient) -> Self {
+        Self { http, language: Language::default(), endpoints: Endpoints::default() }
+    }
+
+    /// Sets the language geocoding results are named in.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Sets the base URLs requests are sent to.
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenMeteo {
+    fn name(&self) -> &'static str {
+        "Open-Meteo"
+    }
+
+    async fn current(&self, location: &Location) -> WeatherResult<CityInfo> {
+        let place = location.place(&self.http, &self.endpoints).await?;
+        let mut params = coordinate_params(&place);
+        params.extend([
+            ("current", CURRENT_VARIABLES.to_string()),
+            ("daily", "temperature_2m_max,temperature_2m_min,sunrise,sunset".to_string()),
+            ("forecast_days", "1".to_string()),
+            ("wind_speed_unit", "ms".to_string()),
+            ("timeformat", "unixtime".to_string()),
+            ("timezone", "auto".to_string()),
+        ]);
+        let url = endpoint(&self.endpoints.open_meteo, "/v1/forecast", &params)?;
+        
+        let body = self.http.get_text(url, None).await?;
+        parse_current(&body, &place)
+    }
+
+    async fn forecast(&self, location: &Location) -> WeatherResult<Forecast> {
+        let place = location.place(&self.http, &self.endpoints).await?;
+        let mut params = coordinate_params(&place);
+        params.extend([
+            ("hourly", "temperature_2m,precipitation_probability,wind_speed_10m,weather_code,is_day".to_string()),
+            ("forecast_days", "5".to_string()),
+            ("wind_speed_unit", "ms".to_string()),
+            ("timeformat", "unixtime".to_string()),
+        ]);
+        let url = endpoint(&self.endpoints.open_meteo, "/v1/forecast", &params)?;
+        
+        let body = self.http.get_text(url, None).await?;
+        parse_forecast(&body, &place)
+    }
+
+    async fn nowcast(&self, lat: f64, lon: f64) -> WeatherResult<Nowcast> {
+        let params = [
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            ("current", "uv_index".to_string()),
+            ("minutely_15", "precipitation".to_string()),
+            // One more quarter than the hour, the first one has mostly passed already
+            ("forecast_minutely_15", "5".to_string()),
+            ("timeformat", "unixtime".to_string()),
+        ];
+        let url = endpoint(&self.endpoints.open_meteo, "/v1/forecast", &params)?;
+        
+        let body = self.http.get_text(url, None).await?;
+        parse_nowcast(&body)
+    }
+
+    async fn history(&self, location: &Location, start: NaiveDate, end: NaiveDate) -> WeatherResult<History> {
+        history(&self.http, &self.endpoints, location, start, end).await
+    }
+
+    async fn geocode(&self, query: &str) -> WeatherResult<Vec<GeoLocation>> {
+        geocode(&self.http, &self.endpoints, query, self.language).await
+    }
+}
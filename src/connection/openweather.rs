@@ -0,0 +1,490 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use serde::Deserialize;
+use crate::i18n::Language;
+use super::{
+    endpoint, open_meteo, parse_json, AirQuality, AirQualityReading, CityInfo, Endpoints, Forecast,
+    ForecastEntry, GeoLocation, History, HttpClient, Location, LocationQuery, MinutePrecipitation, Nowcast,
+    PollutantComponents, WeatherError, WeatherAlert, WeatherProvider, WeatherResult,
+};
+
+/// Default OpenWeather API host.
+pub(crate) const BASE_URL: &str = "https://api.openweathermap.org";
+
+/// Raw `/data/2.5/weather` payload as returned by OpenWeather.
+#[derive(Debug, Deserialize)]
+pub struct CurrentWeatherResponse {
+    pub name: String,
+    pub dt: i64,
+    pub timezone: Option<i32>,     // Shift from UTC in seconds
+    pub coord: CoordSection,
+    pub sys: SysSection,
+    pub main: MainSection,
+    pub weather: Vec<WeatherCondition>,
+    pub wind: WindSection,
+    pub clouds: CloudsSection,
+    pub visibility: Option<u32>,   // Not reported by every station
+    pub rain: Option<PrecipitationSection>,
+    pub snow: Option<PrecipitationSection>,
+}
+
+/// `coord` block shared by several payloads.
+#[derive(Debug, Deserialize)]
+pub struct CoordSection {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// `sys` block of the current weather payload.
+#[derive(Debug, Deserialize)]
+pub struct SysSection {
+    pub country: Option<String>,   // Missing for locations outside any country
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+}
+
+/// `main` block of the current weather payload.
+#[derive(Debug, Deserialize)]
+pub struct MainSection {
+    pub temp: f64,
+    pub feels_like: f64,
+    pub temp_min: f64,
+    pub temp_max: f64,
+    pub pressure: u32,
+    pub humidity: u8,
+}
+
+/// One entry of the `weather` array.
+#[derive(Debug, Deserialize)]
+pub struct WeatherCondition {
+    pub main: String,
+    pub description: String,
+    pub icon: String,
+}
+
+/// `wind` block of the current weather payload.
+#[derive(Debug, Deserialize)]
+pub struct WindSection {
+    pub speed: f64,
+    pub deg: Option<u16>,      // Left out by some stations, e.g. in calm air
+    pub gust: Option<f64>,
+}
+
+/// `clouds` block of the current weather payload.
+#[derive(Debug, Deserialize)]
+pub struct CloudsSection {
+    pub all: u8,
+}
+
+/// `rain` / `snow` block, volumes in millimetres.
+#[derive(Debug, Deserialize)]
+pub struct PrecipitationSection {
+    #[serde(rename = "1h")]
+    pub one_hour: Option<f64>,
+    #[serde(rename = "3h")]
+    pub three_hours: Option<f64>,
+}
+
+impl PrecipitationSection {
+    /// Volume for the last hour, falling back to the 3h total when that is all we got.
+    fn last_hour(&self) -> Option<f64> {
+        self.one_hour.or(self.three_hours)
+    }
+}
+
+impl TryFrom<CurrentWeatherResponse> for CityInfo {
+    type Error = WeatherError;
+
+    fn try_from(data: CurrentWeatherResponse) -> Result<Self, Self::Error> {
+        let condition = data.weather.into_iter().next()
+            .ok_or_else(|| WeatherError::Parse("weather response contains no weather conditions".to_string()))?;
+
+        Ok(CityInfo {
+            name: data.name,
+            country: data.sys.country.unwrap_or_else(|| "--".to_string()),
+            lat: data.coord.lat,
+            lon: data.coord.lon,
+
+            temperature: data.main.temp,
+            feels_like: data.main.feels_like,
+            temp_min: data.main.temp_min,
+            temp_max: data.main.temp_max,
+
+            weather_main: condition.main,
+            description: condition.description,
+            icon: condition.icon,
+
+            humidity: data.main.humidity,
+            pressure: data.main.pressure,
+            wind_speed: data.wind.speed,
+            wind_direction: data.wind.deg,
+            wind_gust: data.wind.gust,
+
+            visibility: data.visibility,
+            clouds: data.clouds.all,
+            rain_1h: data.rain.as_ref().and_then(PrecipitationSection::last_hour),
+            snow_1h: data.snow.as_ref().and_then(PrecipitationSection::last_hour),
+
+            sunrise: data.sys.sunrise.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            sunset: data.sys.sunset.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            hourly_temps: None, // Filled in from the forecast, see `App::fetch_weather`
+
+            utc_offset: data.timezone,
+
+            timestamp: DateTime::from_timestamp(data.dt, 0)
+                .ok_or_else(|| WeatherError::Parse(format!("invalid observation timestamp {}", data.dt)))?,
+        })
+    }
+}
+
+/// Parses a `/data/2.5/weather` body into [`CityInfo`].
+///
+/// Missing required fields are reported as an error instead of being defaulted.
+pub fn parse_current_weather(body: &str) -> WeatherResult<CityInfo> {
+    let data: CurrentWeatherResponse = parse_json(body)?;
+    CityInfo::try_from(data)
+}
+
+/// Raw `/data/2.5/forecast` payload as returned by OpenWeather.
+#[derive(Debug, Deserialize)]
+pub struct ForecastResponse {
+    pub list: Vec<ForecastItem>,
+    pub city: ForecastCity,
+}
+
+/// One 3-hour step of the forecast payload.
+#[derive(Debug, Deserialize)]
+pub struct ForecastItem {
+    pub dt: i64,
+    pub main: MainSection,
+    pub weather: Vec<WeatherCondition>,
+    pub wind: WindSection,
+    pub pop: f64,
+}
+
+/// `city` block of the forecast payload.
+#[derive(Debug, Deserialize)]
+pub struct ForecastCity {
+    pub name: String,
+}
+
+impl TryFrom<ForecastResponse> for Forecast {
+    type Error = WeatherError;
+
+    fn try_from(data: ForecastResponse) -> Result<Self, Self::Error> {
+        let entries = data.list.into_iter()
+            .map(|item| {
+                let condition = item.weather.into_iter().next()
+                    .ok_or_else(|| WeatherError::Parse("forecast entry contains no weather conditions".to_string()))?;
+                Ok(ForecastEntry {
+                    time: DateTime::from_timestamp(item.dt, 0)
+                        .ok_or_else(|| WeatherError::Parse(format!("invalid forecast timestamp {}", item.dt)))?,
+                    temperature: item.main.temp,
+                    precipitation_probability: item.pop,
+                    wind_speed: item.wind.speed,
+                    weather_main: condition.main,
+                    description: condition.description,
+                    icon: condition.icon,
+                })
+            })
+            .collect::<WeatherResult<Vec<_>>>()?;
+
+        Ok(Forecast { city: data.city.name, entries })
+    }
+}
+
+/// Parses a `/data/2.5/forecast` body into a [`Forecast`].
+pub fn parse_forecast(body: &str) -> WeatherResult<Forecast> {
+    let data: ForecastResponse = parse_json(body)?;
+    Forecast::try_from(data)
+}
+
+/// One entry of the `/geo/1.0/direct` payload.
+#[derive(Debug, Deserialize)]
+pub struct GeocodingItem {
+    pub name: String,
+    pub state: Option<String>,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl From<GeocodingItem> for GeoLocation {
+    fn from(item: GeocodingItem) -> Self {
+        GeoLocation {
+            name: item.name,
+            state: item.state,
+            country: item.country,
+            lat: item.lat,
+            lon: item.lon,
+            // OpenWeather reports the offset with the weather instead
+            timezone: None,
+        }
+    }
+}
+
+/// Raw `/data/2.5/air_pollution` (and `/forecast`) payload.
+#[derive(Debug, Deserialize)]
+pub struct AirPollutionResponse {
+    pub list: Vec<AirPollutionItem>,
+}
+
+/// One hourly entry of the air pollution payload.
+#[derive(Debug, Deserialize)]
+pub struct AirPollutionItem {
+    pub dt: i64,
+    pub main: AirPollutionIndex,
+    pub components: PollutantComponents,
+}
+
+/// `main` block of an air pollution entry.
+#[derive(Debug, Deserialize)]
+pub struct AirPollutionIndex {
+    pub aqi: u8,
+}
+
+impl TryFrom<AirPollutionItem> for AirQualityReading {
+    type Error = WeatherError;
+
+    fn try_from(item: AirPollutionItem) -> Result<Self, Self::Error> {
+        Ok(AirQualityReading {
+            time: DateTime::from_timestamp(item.dt, 0)
+                .ok_or_else(|| WeatherError::Parse(format!("invalid air pollution timestamp {}", item.dt)))?,
+            aqi: item.main.aqi,
+            components: item.components,
+        })
+    }
+}
+
+/// Parses an air pollution body into its readings, oldest first.
+pub fn parse_air_pollution(body: &str) -> WeatherResult<Vec<AirQualityReading>> {
+    let data: AirPollutionResponse = parse_json(body)?;
+    data.list.into_iter().map(AirQualityReading::try_from).collect()
+}
+
+/// Raw `/data/3.0/onecall` payload, reduced to the blocks we read.
+#[derive(Debug, Deserialize)]
+pub struct OneCallResponse {
+    pub current: Option<OneCallCurrent>,   // Missing when excluded
+    #[serde(default)]
+    pub minutely: Vec<OneCallMinute>,      // Missing when excluded or not covered
+    #[serde(default)]
+    pub alerts: Vec<OneCallAlert>,   // Missing when nothing is issued
+}
+
+/// `current` block of the One Call payload, reduced to the UV index.
+#[derive(Debug, Deserialize)]
+pub struct OneCallCurrent {
+    pub uvi: Option<f64>,
+}
+
+/// One entry of the One Call `minutely` array.
+#[derive(Debug, Deserialize)]
+pub struct OneCallMinute {
+    pub dt: i64,
+    pub precipitation: f64,   // mm/h
+}
+
+/// One entry of the One Call `alerts` array.
+#[derive(Debug, Deserialize)]
+pub struct OneCallAlert {
+    pub sender_name: String,
+    pub event: String,
+    pub start: i64,
+    pub end: i64,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl TryFrom<OneCallAlert> for WeatherAlert {
+    type Error = WeatherError;
+
+    fn try_from(alert: OneCallAlert) -> Result<Self, Self::Error> {
+        let time = |timestamp| DateTime::from_timestamp(timestamp, 0)
+            .ok_or_else(|| WeatherError::Parse(format!("invalid alert timestamp {}", timestamp)));
+        Ok(WeatherAlert {
+            sender: alert.sender_name,
+            event: alert.event,
+            start: time(alert.start)?,
+            end: time(alert.end)?,
+            description: alert.description,
+            tags: alert.tags,
+        })
+    }
+}
+
+/// Parses a One Call body into its alerts.
+pub fn parse_alerts(body: &str) -> WeatherResult<Vec<WeatherAlert>> {
+    let data: OneCallResponse = parse_json(body)?;
+    data.alerts.into_iter().map(WeatherAlert::try_from).collect()
+}
+
+/// Parses a One Call body into the UV index and the minutely precipitation.
+pub fn parse_nowcast(body: &str) -> WeatherResult<Nowcast> {
+    let data: OneCallResponse = parse_json(body)?;
+    let minutely = data.minutely.into_iter()
+        .map(|minute| Ok(MinutePrecipitation {
+            time: DateTime::from_timestamp(minute.dt, 0)
+                .ok_or_else(|| WeatherError::Parse(format!("invalid minutely timestamp {}", minute.dt)))?,
+            intensity: minute.precipitation,
+        }))
+        .collect::<WeatherResult<Vec<_>>>()?;
+    Ok(Nowcast { uv_index: data.current.and_then(|current| current.uvi), minutely })
+}
+
+/// Query parameters selecting a location in OpenWeather requests.
+fn location_params(location: &Location) -> Vec<(&'static str, String)> {
+    match &location.query {
+        LocationQuery::Name { name } => vec![("q", name.clone())],
+        LocationQuery::Coordinates { lat, lon } => vec![("lat", lat.to_string()), ("lon", lon.to_string())],
+        LocationQuery::Zip { zip, country } => vec![("zip", format!("{},{}", zip, country))],
+        LocationQuery::CityId { id } => vec![("id", id.to_string())],
+    }
+}
+
+/// OpenWeather backend (needs an API key).
+#[derive(Debug, Clone)]
+pub struct OpenWeather {
+    api_key: String,
+    language: Language,
+    endpoints: Endpoints,
+    http: HttpClient,
+}
+
+impl OpenWeather {
+    /// Constructs a new instance of [`OpenWeather`].
+    pub fn new(api_key: impl Into<String>, http: HttpClient) -> Self {
+        Self { api_key: api_key.into(), language: Language::default(), endpoints: Endpoints::default(), http }
+    }
+
+    /// Sets the language condition descriptions and place names are returned in.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Sets the base URLs requests are sent to (history goes to Open-Meteo).
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Appends the key, unit and language parameters every request needs.
+    fn params(&self, mut params: Vec<(&'static str, String)>) -> Vec<(&'static str, String)> {
+        params.push(("appid", self.api_key.clone()));
+        params.push(("units", "metric".to_string()));
+        params.push(("lang", self.language.code().to_string()));
+        params
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenWeather {
+    fn name(&self) -> &'static str {
+        "OpenWeather"
+    }
+
+    async fn current(&self, location: &Location) -> WeatherResult<CityInfo> {
+        // Construct the API URL (using the "weather" endpoint for current weather)
+        let url = endpoint(&self.endpoints.openweather, "/data/2.5/weather", &self.params(location_params(location)))?;
+        
+        let body = self.http.get_text(url, Some(&location.name)).await?;
+        parse_current_weather(&body)
+    }
+
+    async fn forecast(&self, location: &Location) -> WeatherResult<Forecast> {
+        let url = endpoint(&self.endpoints.openweather, "/data/2.5/forecast", &self.params(location_params(location)))?;
+        
+        let body = self.http.get_text(url, Some(&location.name)).await?;
+        parse_forecast(&body)
+    }
+
+    async fn air_quality(&self, lat: f64, lon: f64) -> WeatherResult<AirQuality> {
+        let params = self.params(vec![("lat", lat.to_string()), ("lon", lon.to_string())]);
+        let current_url = endpoint(&self.endpoints.openweather, "/data/2.5/air_pollution", &params)?;
+        let forecast_url = endpoint(&self.endpoints.openweather, "/data/2.5/air_pollution/forecast", &params)?;
+        
+        let (current, forecast) = tokio::join!(
+            self.http.get_text(current_url, None),
+            self.http.get_text(forecast_url, None),
+        );
+        let current = parse_air_pollution(&current?)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeatherError::Parse("air pollution response contains no entries".to_string()))?;
+        // The hourly outlook is a bonus; the gauge still works without it
+        let forecast = forecast
+            .and_then(|body| parse_air_pollution(&body))
+            .unwrap_or_default();
+        
+        Ok(AirQuality { current, forecast })
+    }
+
+    async fn alerts(&self, lat: f64, lon: f64) -> WeatherResult<Vec<WeatherAlert>> {
+        // Only the alerts are wanted, the other One Call blocks are covered by the 2.5 endpoints
+        let params = self.params(vec![
+            ("lat", lat.to_string()),
+            ("lon", lon.to_string()),
+            ("exclude", "current,minutely,hourly,daily".to_string()),
+        ]);
+        let url = endpoint(&self.endpoints.openweather, "/data/3.0/onecall", &params)?;
+        
+        let body = self.http.get_text(url, None).await?;
+        parse_alerts(&body)
+    }
+
+    async fn nowcast(&self, lat: f64, lon: f64) -> WeatherResult<Nowcast> {
+        let params = self.params(vec![
+            ("lat", lat.to_string()),
+            ("lon", lon.to_string()),
+            ("exclude", "hourly,daily,alerts".to_string()),
+        ]);
+        let url = endpoint(&self.endpoints.openweather, "/data/3.0/onecall", &params)?;
+        
+        let body = self.http.get_text(url, None).await?;
+        parse_nowcast(&body)
+    }
+
+    async fn history(&self, location: &Location, start: NaiveDate, end: NaiveDate) -> WeatherResult<History> {
+        // The time machine endpoint answers one hour per request, the Open-Meteo archive a whole range
+        open_meteo::history(&self.http, &self.endpoints, location, start, end).await
+    }
+
+    async fn geocode(&self, query: &str) -> WeatherResult<Vec<GeoLocation>> {
+        let params = self.params(vec![("q", query.to_string()), ("limit", "5".to_string())]);
+        let url = endpoint(&self.endpoints.openweather, "/geo/1.0/direct", &params)?;
+        
+        let body = self.http.get_text(url, Some(query)).await?;
+        let items: Vec<GeocodingItem> = parse_json(&body)?;
+        Ok(items.into_iter().map(GeoLocation::from).collect())
+    }
+}
+
+/// Fetches weather details from the OpenWeather API for the specified city, through the shared client.
+pub async fn get_data(http: &HttpClient, city: String, api_key: &str) -> WeatherResult<CityInfo> {
+    get_data_from(http, &Endpoints::default(), city, api_key).await
+}
+
+/// Fetches weather details for the specified city from the OpenWeather API at `endpoints`.
+pub async fn get_data_from(http: &HttpClient, endpoints: &Endpoints, city: String, api_key: &str) -> WeatherResult<CityInfo> {
+    OpenWeather::new(api_key, http.clone())
+        .with_endpoints(endpoints.clone())
+        .current(&Location::named(city))
+        .await
+}
+
+/// Fetches the 5-day / 3-hour forecast from the OpenWeather API for the specified city.
+pub async fn get_forecast(http: &HttpClient, city: String, api_key: &str) -> WeatherResult<Forecast> {
+    OpenWeather::new(api_key, http.clone()).forecast(&Location::named(city)).await
+}
+
+/// Fetches current air quality and its hourly forecast from the OpenWeather API.
+pub async fn get_air_quality(http: &HttpClient, lat: f64, lon: f64, api_key: &str) -> WeatherResult<AirQuality> {
+    OpenWeather::new(api_key, http.clone()).air_quality(lat, lon).await
+}
+
+/// Fetches the weather alerts issued for the given coordinates (needs a One Call 3.0 subscription).
+pub async fn get_alerts(http: &HttpClient, lat: f64, lon: f64, api_key: &str) -> WeatherResult<Vec<WeatherAlert>> {
+    OpenWeather::new(api_key, http.clone()).alerts(lat, lon).await
+}
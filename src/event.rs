@@ -1,13 +1,18 @@
+use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::NaiveDate;
 use crossterm::event::{Event as CrosstermEvent, KeyEvent, MouseEvent};
 use futures::{FutureExt, StreamExt};
 use tokio::sync::mpsc;
 
 use crate::app::AppResult;
+use crate::connection::{GeoLocation, History, WeatherError};
+use crate::fetch::CityWeather;
+use crate::icons::Icon;
 
-/// Terminal events.
-#[derive(Clone, Copy, Debug)]
+/// Terminal and application events.
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Terminal tick.
     Tick,
@@ -17,6 +22,30 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// A background fetch finished, keyed by [`Location::key`](crate::connection::Location::key).
+    WeatherLoaded(String, Box<CityWeather>),
+    /// A background fetch failed.
+    WeatherFailed(String, WeatherError),
+    /// The history of a city on a date was fetched.
+    HistoryLoaded(String, NaiveDate, Box<History>),
+    /// The history of a city on a date could not be fetched.
+    HistoryFailed(String, NaiveDate, WeatherError),
+    /// The places matching a typed city were looked up.
+    GeocodeLoaded(String, Vec<GeoLocation>),
+    /// The places matching a typed city could not be looked up.
+    GeocodeFailed(String, WeatherError),
+    /// The API key typed in the wizard was checked against the service.
+    KeyChecked(String, Result<(), WeatherError>),
+    /// A condition icon was loaded.
+    IconLoaded(String, Arc<Icon>),
+    /// A condition icon could not be loaded; its glyph is shown instead.
+    IconFailed(String),
+}
+
+/// Posts the outcome of a background task for the main loop.
+pub fn post(sender: &mpsc::UnboundedSender<Event>, event: Event) {
+    // The receiver is gone once the app quits; the outcome is not needed then
+    let _ = sender.send(event);
 }
 
 /// Terminal event handler.
@@ -83,6 +112,11 @@ impl EventsPublisher {
         }
     }
 
+    /// A sender for posting application events into the same queue.
+    pub fn sender(&self) -> mpsc::UnboundedSender<Event> {
+        self.sender.clone()
+    }
+
     /// Receive the next event from the handler thread.
     ///
     /// This function will always block the current thread if
@@ -91,8 +125,7 @@ impl EventsPublisher {
         self.receiver
             .recv()
             .await
-            .ok_or(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            .ok_or(Box::new(std::io::Error::other(
                 "This is an IO error",
             )))
     }
@@ -0,0 +1,151 @@
+use std::future::Future;
+use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::{mpsc, Semaphore};
+use crate::cache::{CacheKind, WeatherCache};
+use crate::event::{post, Event};
+use crate::connection::{
+    AirQuality, CityInfo, Forecast, Location, Nowcast, WeatherAlert, WeatherError, WeatherProvider,
+};
+
+/// Number of forecast steps (3 hours each) shown in the "Next Hours" graph.
+pub const GRAPH_FORECAST_STEPS: usize = 8;
+
+/// Everything fetched for one city.
+#[derive(Debug, Clone)]
+pub struct CityWeather {
+    /// Current conditions
+    pub weather: CityInfo,
+    /// Forecast, if it could be fetched
+    pub forecast: Option<Forecast>,
+    /// Air quality around the city, if the provider has it
+    pub air_quality: Option<AirQuality>,
+    /// Weather alerts issued for the city; empty if there are none or the provider has none
+    pub alerts: Vec<WeatherAlert>,
+    /// UV index and precipitation for the next hour, if the provider has them
+    pub nowcast: Option<Nowcast>,
+    /// When the data was fetched, if it is older than its TTL
+    pub stale_since: Option<DateTime<Utc>>,
+}
+
+/// Fetches the weather of a city through the on-disk cache.
+///
+/// Owns everything it needs, so it can run in a spawned task while the UI keeps drawing.
+#[derive(Debug, Clone)]
+pub struct WeatherFetcher {
+    /// Weather service to ask
+    pub provider: Arc<dyn WeatherProvider>,
+    /// On-disk cache of fetched data, if enabled
+    pub cache: Option<WeatherCache>,
+    /// Only serve cached data, never contact the service
+    pub offline: bool,
+    /// Ask the service even if the cached data is still fresh
+    pub force: bool,
+}
+
+impl WeatherFetcher {
+    /// Fetches `city` in a background task and posts the result as an [`Event`].
+    ///
+    /// The task waits for a permit of `limit` first, which bounds how many fetches run at once.
+    pub fn spawn(self, city: Location, limit: Arc<Semaphore>, sender: mpsc::UnboundedSender<Event>) {
+        tokio::spawn(async move {
+            // The semaphore is never closed, so acquiring only fails if that changes
+            let Ok(_permit) = limit.acquire_owned().await else {
+                return;
+            };
+            let event = match self.fetch(&city).await {
+                Ok(data) => Event::WeatherLoaded(city.key(), Box::new(data)),
+                Err(e) => Event::WeatherFailed(city.key(), e),
+            };
+            post(&sender, event);
+        });
+    }
+
+    /// Fetches the hourly history of `city` on `date` in a background task and posts the result as an [`Event`].
+    pub fn spawn_history(self, city: Location, date: NaiveDate, sender: mpsc::UnboundedSender<Event>) {
+        tokio::spawn(async move {
+            let event = match self.provider.history(&city, date, date).await {
+                Ok(history) => Event::HistoryLoaded(city.key(), date, Box::new(history)),
+                Err(e) => Event::HistoryFailed(city.key(), date, e),
+            };
+            post(&sender, event);
+        });
+    }
+
+    /// Looks up the places matching `query` in a background task and posts them as an [`Event`].
+    pub fn spawn_geocode(self, query: String, sender: mpsc::UnboundedSender<Event>) {
+        tokio::spawn(async move {
+            let event = match self.provider.geocode(&query).await {
+                Ok(candidates) => Event::GeocodeLoaded(query, candidates),
+                Err(e) => Event::GeocodeFailed(query, e),
+            };
+            post(&sender, event);
+        });
+    }
+
+    /// Fetches current conditions, forecast, air quality, alerts and nowcast for one city.
+    pub async fn fetch(&self, city: &Location) -> Result<CityWeather, WeatherError> {
+        let provider = self.provider.clone();
+        let (weather, forecast) = tokio::join!(
+            self.through_cache(CacheKind::Current, city, provider.current(city)),
+            self.through_cache(CacheKind::Forecast, city, provider.forecast(city)),
+        );
+        // A missing forecast only empties the graph, the current conditions are still shown
+        let forecast = forecast.ok().map(|(forecast, _)| forecast);
+        let (mut weather, stale_since) = weather?;
+        weather.hourly_temps = forecast.as_ref().map(|f| f.temperatures(GRAPH_FORECAST_STEPS));
+        // Keyed by the observation point, so they are fetched once we know where that is
+        let (air_quality, alerts, nowcast) = tokio::join!(
+            self.through_cache(CacheKind::AirQuality, city, provider.air_quality(weather.lat, weather.lon)),
+            self.through_cache(CacheKind::Alerts, city, provider.alerts(weather.lat, weather.lon)),
+            self.through_cache(CacheKind::Nowcast, city, provider.nowcast(weather.lat, weather.lon)),
+        );
+        let air_quality = air_quality.ok().map(|(air_quality, _)| air_quality);
+        // One Call needs its own subscription, so a failure here just means no alerts or nowcast
+        let alerts = alerts.map(|(alerts, _)| alerts).unwrap_or_default();
+        let nowcast = nowcast.ok().map(|(nowcast, _)| nowcast);
+        Ok(CityWeather { weather, forecast, air_quality, alerts, nowcast, stale_since })
+    }
+
+    /// Serves `fetch` from the cache while it is fresh (unless forced), stores what it returns otherwise.
+    ///
+    /// Offline, or when the service is temporarily unreachable, the last cached value is
+    /// returned together with the time it was fetched.
+    async fn through_cache<T, F>(
+        &self,
+        kind: CacheKind,
+        location: &Location,
+        fetch: F,
+    ) -> Result<(T, Option<DateTime<Utc>>), WeatherError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = Result<T, WeatherError>>,
+    {
+        let provider = self.provider.name();
+        let cached = self.cache.as_ref().and_then(|cache| cache.load::<T>(kind, provider, location));
+        let stale = match cached {
+            Some(entry) if self.offline => return Ok((entry.data, Some(entry.fetched_at))),
+            None if self.offline => return Err(WeatherError::Offline),
+            Some(entry) if !self.force && self.cache.as_ref().is_some_and(|cache| cache.is_fresh(kind, &entry)) => {
+                return Ok((entry.data, None));
+            },
+            stale => stale,
+        };
+
+        match fetch.await {
+            Ok(data) => {
+                if let Some(cache) = &self.cache {
+                    // A cache that cannot be written only costs us a refetch later
+                    let _ = cache.store(kind, provider, location, &data);
+                }
+                Ok((data, None))
+            },
+            Err(e) if e.is_transient() => stale
+                .map(|entry| (entry.data, Some(entry.fetched_at)))
+                .ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::app::{App, AppResult, InputMode};
-use crossterm::event::{KeyEvent, KeyCode, KeyModifiers};
+use crossterm::event::{KeyEvent, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
@@ -13,6 +13,14 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
                 app.running = false;
             }
             
+            // Move the selected city or group
+            KeyCode::Up if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                app.move_selected(-1);
+            }
+            KeyCode::Down if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                app.move_selected(1);
+            }
+            
             // Navigation - up/down to select cities
             KeyCode::Up => {
                 app.previous_city();
@@ -28,19 +36,60 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
             
             // Delete the selected city
             KeyCode::Char('d') => {
-                if !app.cities.is_empty() {
-                    app.cities.remove(app.selected_city);
-                    if app.selected_city >= app.cities.len() && !app.cities.is_empty() {
-                        app.selected_city = app.cities.len() - 1;
-                    }
-                }
+                app.remove_selected_city();
+            }
+            
+            // Undo and redo edits of the city list
+            KeyCode::Char('u') => {
+                app.undo();
+            }
+            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.redo();
+            }
+            
+            // Rename the selected city
+            KeyCode::Char('n') => {
+                app.open_rename_input();
+            }
+            
+            // Pin the selected city to the top
+            KeyCode::Char('p') => {
+                app.toggle_pin();
+            }
+            
+            // Put the selected city in a group, or rename the selected group
+            KeyCode::Char('g') => {
+                app.open_group_input();
+            }
+            
+            // Collapse or expand the selected group
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                app.toggle_group();
             }
             
-            // Refresh weather data for current city
+            // Show the details of the selected city's weather alerts
+            KeyCode::Char('w') => {
+                app.open_alerts();
+            }
+            
+            // Look up the past weather of the selected city
+            KeyCode::Char('h') => {
+                app.open_history();
+            }
+            
+            // Switch between metric and imperial units
+            KeyCode::Char('m') => {
+                app.toggle_units();
+            }
+            
+            // Refresh weather data for the selected city
             KeyCode::Char('r') => {
-                // If you have an async refresh function, you'll need to handle this differently
-                // This is just a placeholder
-                // app.refresh_weather();
+                app.refresh_selected();
+            }
+            
+            // Refresh weather data for all cities
+            KeyCode::Char('R') => {
+                app.refresh_weather();
             }
             
             _ => {}
@@ -52,11 +101,60 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
                 app.exit_edit_mode();
             }
             
-            // Submit new city
+            // Look up the typed city
+            KeyCode::Enter => {
+                app.search_city();
+            }
+            
+            // Backspace - delete character
+            KeyCode::Backspace => {
+                app.delete_char();
+            }
+            
+            // Type characters
+            KeyCode::Char(c) => {
+                app.handle_input(c);
+            }
+            
+            _ => {}
+        },
+        
+        InputMode::Selecting => match key_event.code {
+            // Back to typing
+            KeyCode::Esc => {
+                app.back_to_editing();
+            }
+            
+            // Navigation between candidates
+            KeyCode::Up => {
+                app.previous_candidate();
+            }
+            KeyCode::Down => {
+                app.next_candidate();
+            }
+            
+            // Add the highlighted candidate
             KeyCode::Enter => {
                 app.add_city();
             }
             
+            _ => {}
+        },
+        
+        InputMode::Grouping | InputMode::Renaming => match key_event.code {
+            // Cancel
+            KeyCode::Esc => {
+                app.exit_edit_mode();
+            }
+            
+            // Save the group or city name
+            KeyCode::Enter if app.input_mode == InputMode::Grouping => {
+                app.apply_group_input();
+            }
+            KeyCode::Enter => {
+                app.apply_rename_input();
+            }
+            
             // Backspace - delete character
             KeyCode::Backspace => {
                 app.delete_char();
@@ -67,9 +165,105 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
                 app.handle_input(c);
             }
             
+            _ => {}
+        },
+        
+        InputMode::Alerts => match key_event.code {
+            // Close the popup
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('w') => {
+                app.close_alerts();
+            }
+            
+            // Scroll through long descriptions
+            KeyCode::Up => {
+                app.scroll_alerts_up();
+            }
+            KeyCode::Down => {
+                app.scroll_alerts_down();
+            }
+            
+            _ => {}
+        },
+        
+        InputMode::History => match key_event.code {
+            // Close the view
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+                app.close_history();
+            }
+            
+            // Pick the day
+            KeyCode::Left => {
+                app.shift_history_date(-1);
+            }
+            KeyCode::Right => {
+                app.shift_history_date(1);
+            }
+            KeyCode::PageUp => {
+                app.shift_history_date(-7);
+            }
+            KeyCode::PageDown => {
+                app.shift_history_date(7);
+            }
+            
+            // Load the picked day
+            KeyCode::Enter => {
+                app.request_history();
+            }
+            
+            _ => {}
+        },
+        
+        InputMode::Setup => match key_event.code {
+            // Previous page, or quit from the first one
+            KeyCode::Esc => {
+                app.setup_back();
+            }
+            
+            // Confirm the page
+            KeyCode::Enter => {
+                app.setup_confirm();
+            }
+            
+            // Choose an option or a place
+            KeyCode::Up => {
+                app.setup_cycle(-1);
+            }
+            KeyCode::Down => {
+                app.setup_cycle(1);
+            }
+            
+            // Type the API key or home city
+            KeyCode::Backspace if app.setup.step.takes_text() => {
+                app.delete_char();
+            }
+            KeyCode::Char(c) if app.setup.step.takes_text() => {
+                app.handle_input(c);
+            }
+            
             _ => {}
         }
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Handles the mouse events: clicking and dragging in the city list.
+pub fn handle_mouse_events(mouse_event: MouseEvent, app: &mut App) -> AppResult<()> {
+    if app.input_mode != InputMode::Normal {
+        return Ok(());
+    }
+    match mouse_event.kind {
+        // Select a city and start dragging it, or fold a group
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.click_city_list(mouse_event.column, mouse_event.row);
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            app.drag_city(mouse_event.row);
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+            app.end_drag();
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,653 @@
+use std::fmt::Display;
+use chrono::{DateTime, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use crate::connection::uv_category;
+
+/// Languages the UI is translated into; also sent to providers that localize descriptions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "ro")]
+    Romanian,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    /// Every supported language, in the order they are offered.
+    pub const ALL: [Language; 3] = [Language::English, Language::Romanian, Language::German];
+
+    /// ISO 639-1 code, as sent to the weather services.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Romanian => "ro",
+            Language::German => "de",
+        }
+    }
+
+    /// Name of the language in the language itself.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Romanian => "Română",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// UI strings in this language.
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Language::English => &EN,
+            Language::Romanian => &RO,
+            Language::German => &DE,
+        }
+    }
+
+    fn chrono_locale(self) -> chrono::Locale {
+        match self {
+            Language::English => chrono::Locale::en_US,
+            Language::Romanian => chrono::Locale::ro_RO,
+            Language::German => chrono::Locale::de_DE,
+        }
+    }
+
+    /// Clock style used when the config does not pick one.
+    fn default_clock(self) -> ClockFormat {
+        match self {
+            Language::English => ClockFormat::TwelveHour,
+            Language::Romanian | Language::German => ClockFormat::TwentyFourHour,
+        }
+    }
+}
+
+/// 12h or 24h clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClockFormat {
+    #[serde(rename = "12h")]
+    TwelveHour,
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+}
+
+/// Locale settings, read from the `[locale]` config section.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocaleSettings {
+    /// UI and description language
+    pub language: Language,
+    /// Clock style; follows the language when not set
+    pub clock: Option<ClockFormat>,
+}
+
+impl LocaleSettings {
+    /// UI strings in the chosen language.
+    pub fn messages(&self) -> &'static Messages {
+        self.language.messages()
+    }
+
+    fn time_pattern(&self) -> &'static str {
+        match self.clock.unwrap_or(self.language.default_clock()) {
+            ClockFormat::TwelveHour => "%-I:%M %p",
+            ClockFormat::TwentyFourHour => "%H:%M",
+        }
+    }
+
+    fn format<Tz: TimeZone>(&self, time: &DateTime<Tz>, pattern: &str) -> String
+    where
+        Tz::Offset: Display,
+    {
+        time.format_localized(pattern, self.language.chrono_locale()).to_string()
+    }
+
+    /// Time of day, e.g. "07:42" or "7:42 AM".
+    pub fn time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        self.format(time, self.time_pattern())
+    }
+
+    /// Weekday and time, e.g. "Mo 15:00" or "Mon 3:00 PM".
+    pub fn weekday_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        self.format(time, &format!("%a {}", self.time_pattern()))
+    }
+
+    fn date_pattern(&self) -> &'static str {
+        match self.language {
+            Language::English => "%b %-d, %Y",
+            Language::Romanian => "%-d %b %Y",
+            Language::German => "%-d. %b %Y",
+        }
+    }
+
+    /// Full date and time, e.g. "16 Oct 2026 08:12" or "Oct 16, 2026 8:12 AM".
+    pub fn date_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        self.format(time, &format!("{} {}", self.date_pattern(), self.time_pattern()))
+    }
+
+    /// Weekday and date, e.g. "Tue, Oct 13, 2026".
+    pub fn date(&self, date: &NaiveDate) -> String {
+        date.format_localized(&format!("%a, {}", self.date_pattern()), self.language.chrono_locale())
+            .to_string()
+    }
+}
+
+/// Replaces each `{}` in `template` with the next argument.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// Translated UI strings. Templates use `{}` placeholders filled in with [`fill`].
+#[derive(Debug)]
+pub struct Messages {
+    pub app_title: &'static str,
+    pub offline_tag: &'static str,
+    pub cities_title: &'static str,
+    pub details_title: &'static str,
+    pub city: &'static str,
+    pub temp: &'static str,
+    pub feels_like: &'static str,
+    pub range: &'static str,
+    pub conditions: &'static str,
+    pub humidity: &'static str,
+    pub wind: &'static str,
+    pub gusts: &'static str,
+    pub visibility: &'static str,
+    pub precipitation: &'static str,
+    pub rain: &'static str,
+    pub snow: &'static str,
+    pub last_hour: &'static str,
+    pub none: &'static str,
+    pub pressure: &'static str,
+    pub sunrise: &'static str,
+    pub sunset: &'static str,
+    pub not_available: &'static str,
+    pub error: &'static str,
+    pub hint: &'static str,
+    pub error_city_not_found: &'static str,
+    pub error_invalid_api_key: &'static str,
+    pub error_rate_limited: &'static str,
+    pub error_network_unreachable: &'static str,
+    pub error_timeout: &'static str,
+    pub error_parse: &'static str,
+    pub error_unsupported: &'static str,
+    pub error_offline: &'static str,
+    pub error_api: &'static str,
+    pub suggest_city_not_found: &'static str,
+    pub suggest_invalid_api_key: &'static str,
+    pub suggest_rate_limited: &'static str,
+    pub suggest_network_unreachable: &'static str,
+    pub suggest_timeout: &'static str,
+    pub suggest_parse: &'static str,
+    pub suggest_unsupported: &'static str,
+    pub suggest_offline: &'static str,
+    pub suggest_api: &'static str,
+    pub no_data: &'static str,
+    pub no_data_hint: &'static str,
+    pub stale_since: &'static str,
+    pub offline_suffix: &'static str,
+    pub next_hours: &'static str,
+    pub forecast_unavailable: &'static str,
+    pub air_quality_title: &'static str,
+    pub air_quality_unavailable: &'static str,
+    pub hourly_aqi: &'static str,
+    pub aqi_levels: [&'static str; 5],
+    pub nowcast_title: &'static str,
+    pub nowcast_unavailable: &'static str,
+    pub uv_index: &'static str,
+    pub uv_levels: [&'static str; 5],
+    pub rain_dry: &'static str,
+    pub rain_starts: &'static str,
+    pub rain_stops: &'static str,
+    pub rain_ongoing: &'static str,
+    pub minutely_unavailable: &'static str,
+    pub retrying: &'static str,
+    pub recovered: &'static str,
+    pub gave_up: &'static str,
+    pub key_hints: &'static str,
+    pub next_refresh: &'static str,
+    pub config_save_failed: &'static str,
+    pub alert_banner: &'static str,
+    pub alert_more: &'static str,
+    pub alert_details_hint: &'static str,
+    pub alerts_title: &'static str,
+    pub alert_sender: &'static str,
+    pub alert_window: &'static str,
+    pub alerts_close_hint: &'static str,
+    pub history_title: &'static str,
+    pub history_hint: &'static str,
+    pub history_loading: &'static str,
+    pub history_empty: &'static str,
+    pub history_columns: [&'static str; 6],
+    pub add_city_title: &'static str,
+    pub add_city_hint: &'static str,
+    pub candidates_hint: &'static str,
+    pub group_title: &'static str,
+    pub group_hint: &'static str,
+    pub rename_title: &'static str,
+    pub rename_hint: &'static str,
+    pub edit_added: &'static str,
+    pub edit_removed: &'static str,
+    pub edit_moved: &'static str,
+    pub edit_moved_group: &'static str,
+    pub edit_renamed: &'static str,
+    pub edit_pinned: &'static str,
+    pub edit_unpinned: &'static str,
+    pub edit_grouped: &'static str,
+    pub edit_ungrouped: &'static str,
+    pub edit_renamed_group: &'static str,
+    pub edit_dissolved_group: &'static str,
+    pub undo_hint: &'static str,
+    pub undone: &'static str,
+    pub redone: &'static str,
+    pub nothing_to_undo: &'static str,
+    pub nothing_to_redo: &'static str,
+    pub searching: &'static str,
+    pub no_match: &'static str,
+    pub setup_title: &'static str,
+    pub setup_step: &'static str,
+    pub setup_provider: &'static str,
+    pub setup_needs_key: &'static str,
+    pub setup_no_key: &'static str,
+    pub setup_api_key: &'static str,
+    pub setup_key_missing: &'static str,
+    pub setup_checking_key: &'static str,
+    pub setup_units: &'static str,
+    pub unit_systems: [&'static str; 2],
+    pub setup_language: &'static str,
+    pub setup_home_city: &'static str,
+    pub setup_choose_hint: &'static str,
+    pub setup_input_hint: &'static str,
+}
+
+impl Messages {
+    /// Name of an air quality index level (1 - 5).
+    pub fn aqi_label(&self, aqi: u8) -> &'static str {
+        self.aqi_levels[usize::from(aqi.clamp(1, 5)) - 1]
+    }
+
+    /// Name of the WHO exposure category of a UV index.
+    pub fn uv_label(&self, uv_index: f64) -> &'static str {
+        self.uv_levels[uv_category(uv_index)]
+    }
+}
+
+pub static EN: Messages = Messages {
+    app_title: "Weather CLI Dashboard",
+    offline_tag: "[offline]",
+    cities_title: " Cities ",
+    details_title: " Weather Details ({}) ",
+    city: "City: ",
+    temp: "Temp: ",
+    feels_like: "feels like",
+    range: "Range: ",
+    conditions: "Conditions: ",
+    humidity: "Humidity: ",
+    wind: "Wind: ",
+    gusts: "gusts",
+    visibility: "Visibility: ",
+    precipitation: "Precipitation: ",
+    rain: "rain",
+    snow: "snow",
+    last_hour: "(1h)",
+    none: "none",
+    pressure: "Pressure: ",
+    sunrise: "Sunrise: ",
+    sunset: "Sunset: ",
+    not_available: "N/A",
+    error: "Error: ",
+    hint: "Hint: ",
+    error_city_not_found: "City \"{}\" was not found",
+    error_invalid_api_key: "Invalid API key",
+    error_rate_limited: "Rate limit exceeded",
+    error_network_unreachable: "Network unreachable: {}",
+    error_timeout: "Request timed out",
+    error_parse: "Could not parse response: {}",
+    error_unsupported: "Not supported: {}",
+    error_offline: "Offline and no cached data available",
+    error_api: "API error ({}): {}",
+    suggest_city_not_found: "Check the spelling or add the country code, e.g. \"Paris,FR\".",
+    suggest_invalid_api_key: "Check your OpenWeather API key; new keys can take a few hours to activate.",
+    suggest_rate_limited: "Wait a minute before refreshing, or use your own API key.",
+    suggest_network_unreachable: "Check your internet connection and proxy settings.",
+    suggest_timeout: "The service is slow to respond; try refreshing again shortly.",
+    suggest_parse: "The service sent unexpected data; try again later.",
+    suggest_unsupported: "Re-add the city by name or coordinates, or switch providers.",
+    suggest_offline: "Run once without --offline to fill the cache.",
+    suggest_api: "Try again later.",
+    no_data: "No weather data available",
+    no_data_hint: "Press r to fetch weather",
+    stale_since: "⚠ Stale since {}",
+    offline_suffix: " (offline)",
+    next_hours: "Next Hours: {} to {} until {}",
+    forecast_unavailable: "Next Hours (forecast unavailable)",
+    air_quality_title: " Air Quality ",
+    air_quality_unavailable: "Air quality unavailable",
+    hourly_aqi: "Hourly AQI",
+    aqi_levels: ["Good", "Fair", "Moderate", "Poor", "Very Poor"],
+    nowcast_title: " Next Hour ",
+    nowcast_unavailable: "UV and minutely precipitation unavailable",
+    uv_index: "UV",
+    uv_levels: ["Low", "Moderate", "High", "Very High", "Extreme"],
+    rain_dry: "No rain in the next hour",
+    rain_starts: "Rain starts in {} min",
+    rain_stops: "Rain stops in {} min",
+    rain_ongoing: "Rain for the whole hour",
+    minutely_unavailable: "No minutely precipitation here",
+    retrying: "Retrying {} ({}/{}) in {}s: {}",
+    recovered: "{} answered after {} retries",
+    gave_up: "Gave up on {} after {} retries: {}",
+    key_hints: "q: quit  ↑/↓: select  ⇧↑/↓: move  a: add  d: delete  u/^R: undo/redo  n: rename  p: pin  g: group  Space: fold  r/R: refresh one/all  m: units  h: history",
+    next_refresh: "Next refresh in {}",
+    config_save_failed: "Could not save settings: {}",
+    alert_banner: "⚠ {} until {}",
+    alert_more: " (+{} more)",
+    alert_details_hint: "  w: details",
+    alerts_title: " Weather Alerts ",
+    alert_sender: "Issued by: ",
+    alert_window: "Valid: ",
+    alerts_close_hint: "Esc: close, ↑/↓: scroll",
+    history_title: " History: {} ",
+    history_hint: "←/→: day  PgUp/PgDn: week  Enter: load  Esc: close",
+    history_loading: "Loading...",
+    history_empty: "No observations for this day",
+    history_columns: ["Time", "Temp", "Conditions", "Precip.", "Wind", "Humidity"],
+    add_city_title: " Add City ",
+    add_city_hint: "Enter: search, Esc: cancel. Also accepts lat,lon / zip:10001,US / id:2643743",
+    candidates_hint: "Enter: add, Esc: back",
+    group_title: " Group ",
+    group_hint: "Enter: save, leave empty to ungroup, Esc: cancel",
+    rename_title: " Rename City ",
+    rename_hint: "Enter: save, Esc: cancel",
+    edit_added: "Added {}",
+    edit_removed: "Removed {}",
+    edit_moved: "Moved {}",
+    edit_moved_group: "Moved group {}",
+    edit_renamed: "Renamed {} to {}",
+    edit_pinned: "Pinned {}",
+    edit_unpinned: "Unpinned {}",
+    edit_grouped: "Moved {} to {}",
+    edit_ungrouped: "Took {} out of its group",
+    edit_renamed_group: "Renamed group {} to {}",
+    edit_dissolved_group: "Dissolved group {}",
+    undo_hint: "{} — press u to undo",
+    undone: "Undone: {}",
+    redone: "Redone: {}",
+    nothing_to_undo: "Nothing to undo",
+    nothing_to_redo: "Nothing to redo",
+    searching: "Searching...",
+    no_match: "No places match \"{}\"",
+    setup_title: " Welcome to Weather CLI ",
+    setup_step: "Step {} of {}",
+    setup_provider: "Which weather service should be used?",
+    setup_needs_key: "free API key needed",
+    setup_no_key: "no key needed",
+    setup_api_key: "Paste your OpenWeather API key (https://home.openweathermap.org/api_keys):",
+    setup_key_missing: "Please enter a key",
+    setup_checking_key: "Checking the key...",
+    setup_units: "Which units do you prefer?",
+    unit_systems: ["Metric (°C, m/s, hPa)", "Imperial (°F, mph, inHg)"],
+    setup_language: "Which language should the dashboard use?",
+    setup_home_city: "What is your home city? Also accepts lat,lon / zip:10001,US / id:2643743",
+    setup_choose_hint: "↑/↓: choose  Enter: next  Esc: back",
+    setup_input_hint: "Enter: next  Esc: back",
+};
+
+pub static RO: Messages = Messages {
+    app_title: "Panou Meteo CLI",
+    offline_tag: "[offline]",
+    cities_title: " Orașe ",
+    details_title: " Detalii Meteo ({}) ",
+    city: "Oraș: ",
+    temp: "Temp: ",
+    feels_like: "resimțită",
+    range: "Interval: ",
+    conditions: "Condiții: ",
+    humidity: "Umiditate: ",
+    wind: "Vânt: ",
+    gusts: "rafale",
+    visibility: "Vizibilitate: ",
+    precipitation: "Precipitații: ",
+    rain: "ploaie",
+    snow: "zăpadă",
+    last_hour: "(1h)",
+    none: "deloc",
+    pressure: "Presiune: ",
+    sunrise: "Răsărit: ",
+    sunset: "Apus: ",
+    not_available: "N/D",
+    error: "Eroare: ",
+    hint: "Sfat: ",
+    error_city_not_found: "Orașul „{}” nu a fost găsit",
+    error_invalid_api_key: "Cheie API invalidă",
+    error_rate_limited: "Limita de cereri a fost depășită",
+    error_network_unreachable: "Rețea inaccesibilă: {}",
+    error_timeout: "Cererea a expirat",
+    error_parse: "Răspunsul nu a putut fi citit: {}",
+    error_unsupported: "Nu este suportat: {}",
+    error_offline: "Offline și fără date în cache",
+    error_api: "Eroare API ({}): {}",
+    suggest_city_not_found: "Verificați ortografia sau adăugați codul țării, de ex. \"Paris,FR\".",
+    suggest_invalid_api_key: "Verificați cheia API OpenWeather; cheile noi pot avea nevoie de câteva ore pentru activare.",
+    suggest_rate_limited: "Așteptați un minut înainte de reîmprospătare sau folosiți propria cheie API.",
+    suggest_network_unreachable: "Verificați conexiunea la internet și setările de proxy.",
+    suggest_timeout: "Serviciul răspunde greu; reîmprospătați din nou în curând.",
+    suggest_parse: "Serviciul a trimis date neașteptate; încercați mai târziu.",
+    suggest_unsupported: "Adăugați orașul din nou după nume sau coordonate, ori schimbați furnizorul.",
+    suggest_offline: "Rulați o dată fără --offline pentru a umple cache-ul.",
+    suggest_api: "Încercați mai târziu.",
+    no_data: "Nu există date meteo",
+    no_data_hint: "Apăsați r pentru a încărca vremea",
+    stale_since: "⚠ Date vechi din {}",
+    offline_suffix: " (offline)",
+    next_hours: "Următoarele ore: {} - {} până la {}",
+    forecast_unavailable: "Următoarele ore (prognoză indisponibilă)",
+    air_quality_title: " Calitatea Aerului ",
+    air_quality_unavailable: "Calitatea aerului indisponibilă",
+    hourly_aqi: "AQI pe ore",
+    aqi_levels: ["Bună", "Acceptabilă", "Moderată", "Slabă", "Foarte slabă"],
+    nowcast_title: " Ora Următoare ",
+    nowcast_unavailable: "UV și precipitații pe minut indisponibile",
+    uv_index: "UV",
+    uv_levels: ["Scăzut", "Moderat", "Ridicat", "Foarte ridicat", "Extrem"],
+    rain_dry: "Fără ploaie în ora următoare",
+    rain_starts: "Începe să plouă în {} min",
+    rain_stops: "Ploaia se oprește în {} min",
+    rain_ongoing: "Plouă toată ora",
+    minutely_unavailable: "Fără precipitații pe minut aici",
+    retrying: "Reîncercare {} ({}/{}) în {}s: {}",
+    recovered: "{} a răspuns după {} reîncercări",
+    gave_up: "Renunțat la {} după {} reîncercări: {}",
+    key_hints: "q: ieșire  ↑/↓: selectare  ⇧↑/↓: mutare  a: adăugare  d: ștergere  u/^R: anulare/refacere  n: redenumire  p: fixare  g: grup  Spațiu: pliere  r/R: reîmprospătare unul/toate  m: unități  h: istoric",
+    next_refresh: "Următoarea actualizare în {}",
+    config_save_failed: "Setările nu au putut fi salvate: {}",
+    alert_banner: "⚠ {} până la {}",
+    alert_more: " (+{} în plus)",
+    alert_details_hint: "  w: detalii",
+    alerts_title: " Avertizări Meteo ",
+    alert_sender: "Emisă de: ",
+    alert_window: "Valabilă: ",
+    alerts_close_hint: "Esc: închidere, ↑/↓: derulare",
+    history_title: " Istoric: {} ",
+    history_hint: "←/→: zi  PgUp/PgDn: săptămână  Enter: încărcare  Esc: închidere",
+    history_loading: "Se încarcă...",
+    history_empty: "Nu există observații pentru această zi",
+    history_columns: ["Ora", "Temp", "Condiții", "Precip.", "Vânt", "Umiditate"],
+    add_city_title: " Adăugare Oraș ",
+    add_city_hint: "Enter: căutare, Esc: anulare. Acceptă și lat,lon / zip:10001,US / id:2643743",
+    candidates_hint: "Enter: adăugare, Esc: înapoi",
+    group_title: " Grup ",
+    group_hint: "Enter: salvare, gol pentru a scoate din grup, Esc: anulare",
+    rename_title: " Redenumire oraș ",
+    rename_hint: "Enter: salvare, Esc: anulare",
+    edit_added: "{} adăugat",
+    edit_removed: "{} șters",
+    edit_moved: "{} mutat",
+    edit_moved_group: "Grupul {} mutat",
+    edit_renamed: "{} redenumit în {}",
+    edit_pinned: "{} fixat",
+    edit_unpinned: "{} nu mai e fixat",
+    edit_grouped: "{} mutat în {}",
+    edit_ungrouped: "{} scos din grup",
+    edit_renamed_group: "Grupul {} redenumit în {}",
+    edit_dissolved_group: "Grupul {} desființat",
+    undo_hint: "{} — apasă u pentru anulare",
+    undone: "Anulat: {}",
+    redone: "Refăcut: {}",
+    nothing_to_undo: "Nimic de anulat",
+    nothing_to_redo: "Nimic de refăcut",
+    searching: "Se caută...",
+    no_match: "Niciun loc nu corespunde cu \"{}\"",
+    setup_title: " Bun venit în Meteo CLI ",
+    setup_step: "Pasul {} din {}",
+    setup_provider: "Ce serviciu meteo vrei să folosești?",
+    setup_needs_key: "necesită o cheie API gratuită",
+    setup_no_key: "fără cheie",
+    setup_api_key: "Lipește cheia API OpenWeather (https://home.openweathermap.org/api_keys):",
+    setup_key_missing: "Introdu o cheie",
+    setup_checking_key: "Se verifică cheia...",
+    setup_units: "Ce unități preferi?",
+    unit_systems: ["Metrice (°C, m/s, hPa)", "Imperiale (°F, mph, inHg)"],
+    setup_language: "În ce limbă să fie afișat panoul?",
+    setup_home_city: "Care este orașul tău? Acceptă și lat,lon / zip:10001,US / id:2643743",
+    setup_choose_hint: "↑/↓: alege  Enter: înainte  Esc: înapoi",
+    setup_input_hint: "Enter: înainte  Esc: înapoi",
+};
+
+pub static DE: Messages = Messages {
+    app_title: "Wetter-CLI-Dashboard",
+    offline_tag: "[offline]",
+    cities_title: " Städte ",
+    details_title: " Wetterdetails ({}) ",
+    city: "Stadt: ",
+    temp: "Temp.: ",
+    feels_like: "gefühlt",
+    range: "Spanne: ",
+    conditions: "Wetterlage: ",
+    humidity: "Luftfeuchte: ",
+    wind: "Wind: ",
+    gusts: "Böen",
+    visibility: "Sichtweite: ",
+    precipitation: "Niederschlag: ",
+    rain: "Regen",
+    snow: "Schnee",
+    last_hour: "(1 Std.)",
+    none: "keiner",
+    pressure: "Luftdruck: ",
+    sunrise: "Sonnenaufgang: ",
+    sunset: "Sonnenuntergang: ",
+    not_available: "k. A.",
+    error: "Fehler: ",
+    hint: "Tipp: ",
+    error_city_not_found: "Stadt „{}“ wurde nicht gefunden",
+    error_invalid_api_key: "Ungültiger API-Schlüssel",
+    error_rate_limited: "Anfragelimit überschritten",
+    error_network_unreachable: "Netzwerk nicht erreichbar: {}",
+    error_timeout: "Zeitüberschreitung der Anfrage",
+    error_parse: "Antwort konnte nicht gelesen werden: {}",
+    error_unsupported: "Nicht unterstützt: {}",
+    error_offline: "Offline und keine Daten im Cache",
+    error_api: "API-Fehler ({}): {}",
+    suggest_city_not_found: "Schreibweise prüfen oder Ländercode anhängen, z. B. \"Paris,FR\".",
+    suggest_invalid_api_key: "OpenWeather-API-Schlüssel prüfen; neue Schlüssel werden oft erst nach einigen Stunden aktiv.",
+    suggest_rate_limited: "Eine Minute mit dem Aktualisieren warten oder einen eigenen API-Schlüssel verwenden.",
+    suggest_network_unreachable: "Internetverbindung und Proxy-Einstellungen prüfen.",
+    suggest_timeout: "Der Dienst antwortet langsam; gleich noch einmal aktualisieren.",
+    suggest_parse: "Der Dienst hat unerwartete Daten gesendet; später erneut versuchen.",
+    suggest_unsupported: "Die Stadt per Name oder Koordinaten neu hinzufügen oder den Anbieter wechseln.",
+    suggest_offline: "Einmal ohne --offline starten, um den Cache zu füllen.",
+    suggest_api: "Später erneut versuchen.",
+    no_data: "Keine Wetterdaten verfügbar",
+    no_data_hint: "r drücken, um das Wetter zu laden",
+    stale_since: "⚠ Veraltet seit {}",
+    offline_suffix: " (offline)",
+    next_hours: "Nächste Stunden: {} - {} bis {}",
+    forecast_unavailable: "Nächste Stunden (keine Vorhersage)",
+    air_quality_title: " Luftqualität ",
+    air_quality_unavailable: "Luftqualität nicht verfügbar",
+    hourly_aqi: "AQI stündlich",
+    aqi_levels: ["Gut", "Mäßig", "Mittel", "Schlecht", "Sehr schlecht"],
+    nowcast_title: " Nächste Stunde ",
+    nowcast_unavailable: "UV und minütlicher Niederschlag nicht verfügbar",
+    uv_index: "UV",
+    uv_levels: ["Niedrig", "Mäßig", "Hoch", "Sehr hoch", "Extrem"],
+    rain_dry: "Kein Regen in der nächsten Stunde",
+    rain_starts: "Regen beginnt in {} Min.",
+    rain_stops: "Regen endet in {} Min.",
+    rain_ongoing: "Regen die ganze Stunde",
+    minutely_unavailable: "Kein minütlicher Niederschlag hier",
+    retrying: "Neuer Versuch bei {} ({}/{}) in {}s: {}",
+    recovered: "{} antwortete nach {} Wiederholungen",
+    gave_up: "{} nach {} Wiederholungen aufgegeben: {}",
+    key_hints: "q: Ende  ↑/↓: Auswahl  ⇧↑/↓: verschieben  a: hinzufügen  d: löschen  u/^R: rückgängig/wiederholen  n: umbenennen  p: anheften  g: Gruppe  Leertaste: einklappen  r/R: eine/alle aktualisieren  m: Einheiten  h: Verlauf",
+    next_refresh: "Aktualisierung in {}",
+    config_save_failed: "Einstellungen konnten nicht gespeichert werden: {}",
+    alert_banner: "⚠ {} bis {}",
+    alert_more: " (+{} weitere)",
+    alert_details_hint: "  w: Details",
+    alerts_title: " Unwetterwarnungen ",
+    alert_sender: "Herausgegeben von: ",
+    alert_window: "Gültig: ",
+    alerts_close_hint: "Esc: schließen, ↑/↓: blättern",
+    history_title: " Verlauf: {} ",
+    history_hint: "←/→: Tag  Bild↑/Bild↓: Woche  Enter: laden  Esc: schließen",
+    history_loading: "Wird geladen...",
+    history_empty: "Keine Beobachtungen für diesen Tag",
+    history_columns: ["Zeit", "Temp.", "Wetterlage", "Niederschl.", "Wind", "Feuchte"],
+    add_city_title: " Stadt hinzufügen ",
+    add_city_hint: "Enter: suchen, Esc: abbrechen. Auch lat,lon / zip:10001,US / id:2643743",
+    candidates_hint: "Enter: hinzufügen, Esc: zurück",
+    group_title: " Gruppe ",
+    group_hint: "Enter: speichern, leer lassen zum Auflösen, Esc: abbrechen",
+    rename_title: " Stadt umbenennen ",
+    rename_hint: "Enter: speichern, Esc: abbrechen",
+    edit_added: "{} hinzugefügt",
+    edit_removed: "{} entfernt",
+    edit_moved: "{} verschoben",
+    edit_moved_group: "Gruppe {} verschoben",
+    edit_renamed: "{} in {} umbenannt",
+    edit_pinned: "{} angeheftet",
+    edit_unpinned: "{} nicht mehr angeheftet",
+    edit_grouped: "{} nach {} verschoben",
+    edit_ungrouped: "{} aus der Gruppe genommen",
+    edit_renamed_group: "Gruppe {} in {} umbenannt",
+    edit_dissolved_group: "Gruppe {} aufgelöst",
+    undo_hint: "{} — u zum Rückgängigmachen",
+    undone: "Rückgängig: {}",
+    redone: "Wiederhergestellt: {}",
+    nothing_to_undo: "Nichts rückgängig zu machen",
+    nothing_to_redo: "Nichts wiederherzustellen",
+    searching: "Suche...",
+    no_match: "Keine Orte für \"{}\" gefunden",
+    setup_title: " Willkommen bei Wetter-CLI ",
+    setup_step: "Schritt {} von {}",
+    setup_provider: "Welcher Wetterdienst soll verwendet werden?",
+    setup_needs_key: "kostenloser API-Schlüssel nötig",
+    setup_no_key: "kein Schlüssel nötig",
+    setup_api_key: "OpenWeather-API-Schlüssel einfügen (https://home.openweathermap.org/api_keys):",
+    setup_key_missing: "Bitte einen Schlüssel eingeben",
+    setup_checking_key: "Schlüssel wird geprüft...",
+    setup_units: "Welche Einheiten bevorzugst du?",
+    unit_systems: ["Metrisch (°C, m/s, hPa)", "Imperial (°F, mph, inHg)"],
+    setup_language: "In welcher Sprache soll das Dashboard erscheinen?",
+    setup_home_city: "Was ist deine Heimatstadt? Auch lat,lon / zip:10001,US / id:2643743",
+    setup_choose_hint: "↑/↓: wählen  Enter: weiter  Esc: zurück",
+    setup_input_hint: "Enter: weiter  Esc: zurück",
+};
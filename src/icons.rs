@@ -0,0 +1,324 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use base64::Engine;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use crate::cache::write_atomically;
+use crate::connection::{get_icon_url, HttpClient, WeatherError, WeatherResult};
+use crate::event::{post, Event};
+
+/// Icon settings, read from the `[icons]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IconSettings {
+    /// Whether condition images are downloaded at all; glyphs are shown otherwise
+    pub enabled: bool,
+    /// How images are drawn
+    pub mode: IconMode,
+}
+
+impl Default for IconSettings {
+    fn default() -> Self {
+        Self { enabled: true, mode: IconMode::Auto }
+    }
+}
+
+impl IconSettings {
+    /// The protocol to draw icons with, detecting the terminal's capabilities in `auto` mode.
+    pub fn protocol(&self) -> GraphicsProtocol {
+        if !self.enabled {
+            return GraphicsProtocol::Glyphs;
+        }
+        match self.mode {
+            IconMode::Auto => GraphicsProtocol::detect(),
+            IconMode::Kitty => GraphicsProtocol::Kitty,
+            IconMode::Sixel => GraphicsProtocol::Sixel,
+            IconMode::HalfBlocks => GraphicsProtocol::HalfBlocks,
+            IconMode::Glyphs => GraphicsProtocol::Glyphs,
+        }
+    }
+}
+
+/// Icon drawing mode as configured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IconMode {
+    #[default]
+    Auto,
+    Kitty,
+    Sixel,
+    HalfBlocks,
+    Glyphs,
+}
+
+/// How icons end up on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Kitty graphics protocol, the PNG is sent as is
+    Kitty,
+    /// DEC sixel graphics
+    Sixel,
+    /// Upper half blocks with 24-bit colours, two pixels per cell
+    HalfBlocks,
+    /// Built-in Unicode glyphs, no images
+    Glyphs,
+}
+
+impl GraphicsProtocol {
+    /// Best protocol the terminal advertises through its environment.
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || ["WezTerm", "ghostty"].contains(&program.as_str())
+        {
+            GraphicsProtocol::Kitty
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+            GraphicsProtocol::Sixel
+        } else if ["truecolor", "24bit"].contains(&var("COLORTERM").as_str()) {
+            GraphicsProtocol::HalfBlocks
+        } else {
+            GraphicsProtocol::Glyphs
+        }
+    }
+
+    /// Whether the image is written to the terminal after the frame rather than into the buffer.
+    pub fn is_overlay(self) -> bool {
+        matches!(self, GraphicsProtocol::Kitty | GraphicsProtocol::Sixel)
+    }
+}
+
+/// A decoded condition icon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Icon {
+    /// OpenWeather icon ID, e.g. "10d"
+    pub code: String,
+    /// The PNG as downloaded
+    pub png: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, row by row
+    pub rgba: Vec<u8>,
+}
+
+impl Icon {
+    /// Decodes a PNG into RGBA pixels.
+    pub fn decode(code: &str, png: Vec<u8>) -> WeatherResult<Self> {
+        let parse_error = |e: png::DecodingError| WeatherError::Parse(format!("icon {}: {}", code, e));
+        let mut decoder = png::Decoder::new(png.as_slice());
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(parse_error)?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut pixels).map_err(parse_error)?;
+        pixels.truncate(frame.buffer_size());
+        let rgba = match frame.color_type {
+            png::ColorType::Rgba => pixels,
+            png::ColorType::Rgb => pixels.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => pixels.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => pixels.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(WeatherError::Parse(format!("icon {}: unexpanded palette", code)));
+            }
+        };
+        Ok(Self { code: code.to_string(), png, width: frame.width, height: frame.height, rgba })
+    }
+
+    /// The pixel nearest to a position given as fractions of the width and height.
+    pub fn sample(&self, x: f64, y: f64) -> [u8; 4] {
+        let px = ((x * self.width as f64) as u32).min(self.width.saturating_sub(1));
+        let py = ((y * self.height as f64) as u32).min(self.height.saturating_sub(1));
+        let i = ((py * self.width + px) * 4) as usize;
+        match self.rgba.get(i..i + 4) {
+            Some(p) => [p[0], p[1], p[2], p[3]],
+            None => [0; 4],
+        }
+    }
+}
+
+/// Loads an icon from `dir`, downloading and storing it there if it is missing.
+///
+/// Icons never change, so a stored one is used regardless of its age.
+pub async fn load_icon(http: &HttpClient, dir: Option<PathBuf>, code: &str, offline: bool) -> WeatherResult<Icon> {
+    let path = dir.as_ref().map(|dir| dir.join(format!("{}.png", code)));
+    if let Some(png) = path.as_ref().and_then(|path| fs::read(path).ok()) {
+        return Icon::decode(code, png);
+    }
+    if offline {
+        return Err(WeatherError::Offline);
+    }
+    let url = Url::parse(&get_icon_url(code))
+        .map_err(|e| WeatherError::NetworkUnreachable(format!("invalid icon URL: {}", e)))?;
+    let png = http.get_bytes(url, None).await?;
+    let icon = Icon::decode(code, png)?;
+    if let (Some(dir), Some(path)) = (&dir, &path) {
+        // Failing to store it only means downloading it again next time
+        let _ = fs::create_dir_all(dir).and_then(|_| write_atomically(path, &icon.png));
+    }
+    Ok(icon)
+}
+
+/// Loads an icon in a background task and posts the result as an [`Event`].
+pub fn spawn_load(http: HttpClient, dir: Option<PathBuf>, code: String, offline: bool, sender: mpsc::UnboundedSender<Event>) {
+    tokio::spawn(async move {
+        let event = match load_icon(&http, dir, &code, offline).await {
+            Ok(icon) => Event::IconLoaded(code, Arc::new(icon)),
+            Err(_) => Event::IconFailed(code),
+        };
+        post(&sender, event);
+    });
+}
+
+/// Unicode stand-in for an OpenWeather icon ID.
+pub fn glyph(code: &str) -> &'static str {
+    let night = code.ends_with('n');
+    match code.get(..2).unwrap_or_default() {
+        "01" if night => "☾",
+        "01" => "☀",
+        "02" => "⛅",
+        "03" | "04" => "☁",
+        "09" => "☂",
+        "10" => "☔",
+        "11" => "⚡",
+        "13" => "❄",
+        "50" => "≋",
+        _ => "?",
+    }
+}
+
+/// Draws an icon with upper half blocks: the foreground is the top pixel, the background the bottom one.
+pub struct HalfBlockIcon<'a> {
+    icon: &'a Icon,
+}
+
+impl<'a> HalfBlockIcon<'a> {
+    /// Constructs a new instance of [`HalfBlockIcon`].
+    pub fn new(icon: &'a Icon) -> Self {
+        Self { icon }
+    }
+}
+
+impl Widget for HalfBlockIcon<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let rows = f64::from(area.height) * 2.0;
+        let cols = f64::from(area.width);
+        let color = |[r, g, b, a]: [u8; 4]| (a >= 128).then_some(Color::Rgb(r, g, b));
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let fx = (f64::from(x) + 0.5) / cols;
+                let top = color(self.icon.sample(fx, (f64::from(y) * 2.0 + 0.5) / rows));
+                let bottom = color(self.icon.sample(fx, (f64::from(y) * 2.0 + 1.5) / rows));
+                let cell = buf.get_mut(area.x + x, area.y + y);
+                match (top, bottom) {
+                    (Some(top), bottom) => {
+                        cell.set_symbol("▀").set_fg(top).set_bg(bottom.unwrap_or(Color::Reset));
+                    }
+                    (None, Some(bottom)) => {
+                        cell.set_symbol("▄").set_fg(bottom).set_bg(Color::Reset);
+                    }
+                    (None, None) => {
+                        cell.set_symbol(" ");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Kitty graphics sequence showing the icon scaled to `columns` x `rows` cells at the cursor.
+pub fn kitty_sequence(icon: &Icon, columns: u16, rows: u16) -> String {
+    let data = base64::engine::general_purpose::STANDARD.encode(&icon.png);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            // f=100: PNG, a=T: transmit and show, q=2: no replies, C=1: leave the cursor alone
+            let _ = write!(out, "\x1b_Gf=100,a=T,q=2,C=1,c={},r={},m={};{}\x1b\\", columns, rows, more, chunk);
+        } else {
+            let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    out
+}
+
+/// Kitty graphics sequence removing every image we placed.
+pub const KITTY_CLEAR: &str = "\x1b_Ga=d,q=2\x1b\\";
+
+/// Sixel sequence showing the icon scaled to `width` x `height` pixels at the cursor.
+///
+/// Colours are reduced to a 6x6x6 cube; transparent pixels are left as they are.
+pub fn sixel_sequence(icon: &Icon, width: u32, height: u32) -> String {
+    let level = |value: u8| (u16::from(value) * 5 + 127) / 255;
+    let mut indices = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b, a] = icon.sample(
+                (f64::from(x) + 0.5) / f64::from(width),
+                (f64::from(y) + 0.5) / f64::from(height),
+            );
+            indices.push((a >= 128).then(|| level(r) * 36 + level(g) * 6 + level(b)));
+        }
+    }
+
+    // P2=1: pixels we do not set keep their current colour
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    for color in 0..216u16 {
+        if indices.contains(&Some(color)) {
+            let percent = |component: u16| component * 100 / 5;
+            let _ = write!(out, "#{};2;{};{};{}", color, percent(color / 36), percent(color / 6 % 6), percent(color % 6));
+        }
+    }
+    for band in 0..height.div_ceil(6) {
+        let mut colors: Vec<u16> = Vec::new();
+        for y in band * 6..((band + 1) * 6).min(height) {
+            for x in 0..width {
+                if let Some(color) = indices[(y * width + x) as usize] {
+                    if !colors.contains(&color) {
+                        colors.push(color);
+                    }
+                }
+            }
+        }
+        for color in colors {
+            let _ = write!(out, "#{}", color);
+            let column = |x: u32| {
+                (0..6).filter(|bit| {
+                    let y = band * 6 + bit;
+                    y < height && indices[(y * width + x) as usize] == Some(color)
+                })
+                .fold(0u8, |sixel, bit| sixel | 1 << bit)
+            };
+            // Run-length encode repeated columns
+            let mut x = 0;
+            while x < width {
+                let sixel = column(x);
+                let mut run = 1;
+                while x + run < width && column(x + run) == sixel {
+                    run += 1;
+                }
+                let symbol = char::from(63 + sixel);
+                if run > 3 {
+                    let _ = write!(out, "!{}{}", run, symbol);
+                } else {
+                    (0..run).for_each(|_| out.push(symbol));
+                }
+                x += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
@@ -13,4 +13,39 @@ pub mod tui;
 /// Event handler.
 pub mod handler;
 
-pub mod connection;
\ No newline at end of file
+/// Weather services.
+pub mod connection;
+
+/// User configuration.
+pub mod config;
+
+/// City list order, pins and groups.
+pub mod cities;
+
+/// API key lookup and redaction.
+pub mod secrets;
+
+/// First-run setup wizard.
+pub mod setup;
+
+/// On-disk cache of fetched weather data.
+pub mod cache;
+
+/// Background weather fetches.
+pub mod fetch;
+
+/// Automatic refresh policy.
+pub mod refresh;
+
+/// Condition icons drawn in the terminal.
+pub mod icons;
+
+/// Command line arguments.
+pub mod cli;
+
+/// Unit conversions for display.
+pub mod units;
+
+/// UI translations and locale-aware formatting.
+pub mod i18n;
+
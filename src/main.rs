@@ -1,6 +1,10 @@
+use clap::Parser;
 use ratatui_templates::app::{App, AppResult};
+use ratatui_templates::cli::Cli;
+use ratatui_templates::config::Config;
 use ratatui_templates::event::{Event, EventsPublisher};
-use ratatui_templates::handler::handle_key_events;
+use ratatui_templates::handler::{handle_key_events, handle_mouse_events};
+use ratatui_templates::secrets::{resolve_api_key, API_KEY_ENV};
 use ratatui_templates::tui::Tui;
 use std::io;
 use ratatui::backend::CrosstermBackend;
@@ -8,8 +12,30 @@ use ratatui::Terminal;
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
+    let cli = Cli::parse();
+    
     // Create an application.
-    let mut app = App::new();
+    let config_path = cli.config.or_else(Config::default_path);
+    let config = match &config_path {
+        Some(path) => Config::load_from(path)?,
+        None => Config::default(),
+    };
+    // Without a config file the wizard asks for everything, including the key
+    let first_run = !cli.offline && config_path.as_ref().is_some_and(|path| !path.exists());
+    let api_key = match resolve_api_key(cli.api_key.as_deref(), &config.api_key)? {
+        Some(key) => key,
+        None if config.provider.needs_api_key() && !first_run => {
+            return Err(format!(
+                "no OpenWeather API key found; pass --api-key, set {}, add it to the [api_key] \
+                 section of the config or switch to provider = \"open-meteo\"",
+                API_KEY_ENV
+            ).into());
+        }
+        None => String::new(),
+    };
+    let mut app = App::from_config(&config, api_key);
+    app.offline = cli.offline;
+    app.config_path = config_path;
 
     // Setup the terminal
     let backend = CrosstermBackend::new(io::stderr());
@@ -17,22 +43,40 @@ async fn main() -> AppResult<()> {
 
 
     // TODO: create the events pubisher
-    let mut tick_rate = 100;
-    let mut events_publisher= EventsPublisher::new(tick_rate);
+    let tick_rate = 100;
+    let events_publisher= EventsPublisher::new(tick_rate);
 
     // TODO: init the terminal user interface
     let mut tui = Tui::new(terminal, events_publisher);
     tui.init()?;
     // Start the main loop.
 
-    app.request_weather_fetch();
-    match app.fetch_weather().await {
-        Ok(_) => {},
-        Err(e) => eprintln!("Error fetching initial weather: {:?}", e),
+    if first_run {
+        app.start_setup();
+    } else {
+        app.load_weather();
     }
-    app.fetch_requested = false;
 
+    let fetch_results = tui.events.sender();
     while app.running {
+        if app.fetch_requested {
+            app.fetch_weather(&fetch_results);
+        }
+        if app.geocode_requested {
+            app.geocode_input(&fetch_results);
+        }
+        if app.setup.key_check_requested {
+            app.check_setup_key(&fetch_results);
+        }
+        if app.history_requested {
+            app.fetch_history(&fetch_results);
+        }
+        if !app.pending_icons.is_empty() {
+            app.fetch_icons(&fetch_results);
+        }
+        if app.config_changed {
+            app.save_config();
+        }
         // TODO: Render the user interface.
         tui.draw(&mut app).expect("failed to draw the user interface");
         // TODO: Handle events.
@@ -41,13 +85,10 @@ async fn main() -> AppResult<()> {
             Ok(event) => {
                 match event {
                     Event::Key(key) => {
-                        handle_key_events(key, &mut app);
+                        handle_key_events(key, &mut app)?;
                     }
-                    Event::Mouse(_mouse) => {
-                        // We don't have a separate mouse handler
-                        // You could handle mouse events here directly or ignore them
-                        // Alternatively, you could pass a KeyEvent equivalent
-                        // handle_key_events(KeyEvent::new(KeyCode::Null, KeyModifiers::NONE), &mut app);
+                    Event::Mouse(mouse) => {
+                        handle_mouse_events(mouse, &mut app)?;
                     }
                     Event::Resize(width, height) => {
                         // Optional: handle resize events if needed
@@ -57,19 +98,44 @@ async fn main() -> AppResult<()> {
                         // Update any time-based logic
                         app.tick();
                     }
+                    Event::WeatherLoaded(key, data) => {
+                        app.weather_loaded(key, *data);
+                    }
+                    Event::WeatherFailed(key, error) => {
+                        app.weather_failed(key, error);
+                    }
+                    Event::HistoryLoaded(key, date, history) => {
+                        app.history_loaded(key, date, *history);
+                    }
+                    Event::HistoryFailed(key, date, error) => {
+                        app.history_failed(key, date, error);
+                    }
+                    Event::GeocodeLoaded(query, candidates) => {
+                        app.geocode_loaded(query, candidates);
+                    }
+                    Event::GeocodeFailed(query, error) => {
+                        app.geocode_failed(query, error);
+                    }
+                    Event::KeyChecked(key, result) => {
+                        app.key_checked(key, result);
+                    }
+                    Event::IconLoaded(code, icon) => {
+                        app.icon_loaded(code, icon);
+                    }
+                    Event::IconFailed(_code) => {
+                        // The entry stays empty, so the glyph is shown and no retry is made
+                    }
                 }
             },
             Err(e) => {
                 eprintln!("Error receiving event: {:?}", e);
             }
         }
-        if app.fetch_requested {
-            match app.fetch_weather().await {
-                Ok(_) => {},
-                Err(e) => eprintln!("Error fetching weather: {:?}", e),
-            }
-            app.fetch_requested = false;
-        }
+    }
+
+    // The selection is not written while browsing, only when leaving
+    if app.selection_changed {
+        app.save_config();
     }
 
     // TODO: Reset the terminal if the app has been terminated
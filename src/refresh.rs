@@ -0,0 +1,67 @@
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::connection::Location;
+
+/// Automatic refresh policy, read from the `[refresh]` config section.
+///
+/// ```toml
+/// [refresh]
+/// interval_secs = 900
+/// jitter_secs = 60
+///
+/// [refresh.cities]
+/// Tokyo = 300
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshSettings {
+    /// Whether cities are refreshed automatically at all
+    pub enabled: bool,
+    /// Seconds between two refreshes of a city
+    pub interval_secs: u64,
+    /// Up to this many seconds are added to each interval, so cities do not all fire at once
+    pub jitter_secs: u64,
+    /// Interval overrides by the name a city has in the list; renaming it in the app moves its override along
+    pub cities: BTreeMap<String, u64>,
+}
+
+impl Default for RefreshSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 600,
+            jitter_secs: 30,
+            cities: BTreeMap::new(),
+        }
+    }
+}
+
+impl RefreshSettings {
+    /// Interval for one city, `None` if automatic refresh is off.
+    pub fn interval_for(&self, location: &Location) -> Option<Duration> {
+        let secs = self.cities.get(&location.name).copied().unwrap_or(self.interval_secs);
+        (self.enabled && secs > 0).then_some(Duration::from_secs(secs))
+    }
+
+    /// Moves the interval override of a city renamed from `from` to `to`, if it has one.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(secs) = self.cities.remove(from) {
+            self.cities.insert(to.to_string(), secs);
+        }
+    }
+
+    /// When a city fetched at `fetched` is due again, jitter included.
+    pub fn next_refresh(&self, location: &Location, fetched: Instant) -> Option<Instant> {
+        self.interval_for(location).map(|interval| fetched + interval + jitter(self.jitter_secs))
+    }
+}
+
+/// A random delay of up to `max_secs` seconds.
+fn jitter(max_secs: u64) -> Duration {
+    // A freshly seeded hasher is random enough to spread refreshes out
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_millis(random % max_secs.saturating_mul(1000).saturating_add(1))
+}
@@ -0,0 +1,167 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use crate::app::AppResult;
+
+/// Environment variable the API key is read from.
+pub const API_KEY_ENV: &str = "OPENWEATHER_API_KEY";
+
+/// Query parameters that carry credentials and are masked by [`redact_url`].
+const SECRET_PARAMS: [&str; 3] = ["appid", "apikey", "api_key"];
+
+/// Where to find the API key, read from the `[api_key]` config section.
+///
+/// ```toml
+/// [api_key]
+/// # Any one of these; they are tried in this order
+/// value = "0123456789abcdef"
+/// file = "~/.config/weather-cli/api_key"
+/// command = "pass show owm"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiKeySettings {
+    /// The key itself; the config file must then not be readable by group or others
+    pub value: Option<String>,
+    /// File holding the key; must not be readable by group or others
+    pub file: Option<PathBuf>,
+    /// Shell command printing the key
+    pub command: Option<String>,
+}
+
+impl ApiKeySettings {
+    /// Default key file, e.g. `~/.config/weather-cli/api_key`; used when no other file is configured.
+    pub fn default_file() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("weather-cli").join("api_key"))
+    }
+}
+
+/// Finds the API key, trying in order: the `--api-key` flag, the [`API_KEY_ENV`] variable,
+/// the config value, the key file and the key command.
+///
+/// Returns `None` if no source has one; a key file that is readable by others or a failing
+/// command is an error rather than being skipped.
+pub fn resolve_api_key(flag: Option<&str>, settings: &ApiKeySettings) -> AppResult<Option<String>> {
+    let non_empty = |key: &str| Some(key.trim().to_string()).filter(|key| !key.is_empty());
+    if let Some(key) = flag.and_then(non_empty) {
+        return Ok(Some(key));
+    }
+    if let Some(key) = env::var(API_KEY_ENV).ok().as_deref().and_then(non_empty) {
+        return Ok(Some(key));
+    }
+    if let Some(key) = settings.value.as_deref().and_then(non_empty) {
+        return Ok(Some(key));
+    }
+    let file = settings.file.as_ref().map(|file| expand_home(file)).or_else(ApiKeySettings::default_file);
+    if let Some(key) = file.as_deref().map(read_key_file).transpose()?.flatten() {
+        return Ok(Some(key));
+    }
+    match &settings.command {
+        Some(command) => run_key_command(command).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Reads a key file, refusing files other users can read; `None` if it does not exist.
+fn read_key_file(path: &Path) -> AppResult<Option<String>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("cannot read API key file {}: {}", path.display(), e).into()),
+    };
+    check_private(path, "API key file")?;
+    Ok(Some(contents.trim().to_string()).filter(|key| !key.is_empty()))
+}
+
+/// Refuses a config file other users can read if it holds the API key itself.
+pub fn check_config_file(path: &Path, settings: &ApiKeySettings) -> AppResult<()> {
+    if settings.value.as_deref().is_some_and(|key| !key.trim().is_empty()) {
+        check_private(path, "config file with an API key")?;
+    }
+    Ok(())
+}
+
+/// Fails if group or others have any access to `path`; `what` names the file in the error.
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn check_private(path: &Path, what: &str) -> AppResult<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            return Err(format!(
+                "{} {} is accessible by other users (mode {:o}); run `chmod 600 {}`",
+                what, path.display(), mode & 0o777, path.display()
+            ).into());
+        }
+    }
+    Ok(())
+}
+
+/// Writes a key file readable only by the current user, creating its directory if needed.
+pub fn write_key_file(path: &Path, key: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", key)
+}
+
+/// Runs the key command through the shell and returns the first line it prints.
+fn run_key_command(command: &str) -> AppResult<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|e| format!("cannot run API key command `{}`: {}", command, e))?;
+    if !output.status.success() {
+        return Err(format!("API key command `{}` failed: {}", command, output.status).into());
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| format!("API key command `{}` printed nothing", command).into())
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The URL with credentials in its query masked, safe to show in errors and logs.
+pub fn redact_url(url: &Url) -> Url {
+    if !url.query_pairs().any(|(name, _)| SECRET_PARAMS.contains(&name.as_ref())) {
+        return url.clone();
+    }
+    let pairs: Vec<(String, String)> = url.query_pairs()
+        .map(|(name, value)| {
+            let value = if SECRET_PARAMS.contains(&name.as_ref()) { "REDACTED".into() } else { value };
+            (name.into_owned(), value.into_owned())
+        })
+        .collect();
+    let mut redacted = url.clone();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use crate::connection::{Location, ProviderKind, WeatherProvider};
+use crate::event::{post, Event};
+use crate::i18n::Language;
+use crate::units::UnitSystem;
+
+/// City the API key is checked against during setup.
+pub const KEY_CHECK_CITY: &str = "London";
+
+/// Checks `key` with a request through `provider` in a background task and posts the outcome as an [`Event`].
+pub fn spawn_key_check(provider: Arc<dyn WeatherProvider>, key: String, sender: mpsc::UnboundedSender<Event>) {
+    tokio::spawn(async move {
+        let result = provider.current(&Location::named(KEY_CHECK_CITY)).await.map(|_| ());
+        post(&sender, Event::KeyChecked(key, result));
+    });
+}
+
+/// Pages of the first-run wizard, in order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SetupStep {
+    #[default]
+    Provider,
+    /// Only shown for services that need a key
+    ApiKey,
+    Units,
+    Language,
+    HomeCity,
+}
+
+impl SetupStep {
+    /// Whether the page asks for typed text rather than a choice from a list.
+    pub fn takes_text(self) -> bool {
+        matches!(self, SetupStep::ApiKey | SetupStep::HomeCity)
+    }
+}
+
+/// Choices made so far in the first-run wizard.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Setup {
+    /// Page currently shown
+    pub step: SetupStep,
+    /// Weather service to use
+    pub provider: ProviderKind,
+    /// Units values are displayed in
+    pub units: UnitSystem,
+    /// UI language; applied right away so the rest of the wizard is translated
+    pub language: Language,
+    /// Whether the API key was typed in the wizard and should be written to a key file
+    pub save_key: bool,
+    /// Flag to indicate the typed API key should be checked against the service
+    pub key_check_requested: bool,
+}
+
+impl Setup {
+    /// The pages to go through with the current choices.
+    pub fn steps(&self) -> Vec<SetupStep> {
+        [SetupStep::Provider, SetupStep::ApiKey, SetupStep::Units, SetupStep::Language, SetupStep::HomeCity]
+            .into_iter()
+            .filter(|&step| step != SetupStep::ApiKey || self.provider.needs_api_key())
+            .collect()
+    }
+
+    /// Number of the current page, starting at 1, and the number of pages.
+    pub fn progress(&self) -> (usize, usize) {
+        let steps = self.steps();
+        let current = steps.iter().position(|&step| step == self.step).unwrap_or(0);
+        (current + 1, steps.len())
+    }
+
+    /// Moves to the next page; returns false on the last one.
+    pub fn next_step(&mut self) -> bool {
+        self.move_step(1)
+    }
+
+    /// Moves to the previous page; returns false on the first one.
+    pub fn previous_step(&mut self) -> bool {
+        self.move_step(-1)
+    }
+
+    fn move_step(&mut self, delta: isize) -> bool {
+        let steps = self.steps();
+        let current = steps.iter().position(|&step| step == self.step).unwrap_or(0);
+        match current.checked_add_signed(delta).and_then(|index| steps.get(index)) {
+            Some(&step) => {
+                self.step = step;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Highlights the next (`delta` 1) or previous (-1) option on pages that offer a choice.
+    pub fn cycle_option(&mut self, delta: isize) {
+        match self.step {
+            SetupStep::Provider => self.provider = cycle(&ProviderKind::ALL, self.provider, delta),
+            SetupStep::Units => self.units = cycle(&UnitSystem::ALL, self.units, delta),
+            SetupStep::Language => self.language = cycle(&Language::ALL, self.language, delta),
+            SetupStep::ApiKey | SetupStep::HomeCity => {}
+        }
+    }
+}
+
+/// The option `delta` places away from `current`, wrapping around.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, delta: isize) -> T {
+    let index = options.iter().position(|&option| option == current).unwrap_or(0) as isize;
+    options[(index + delta).rem_euclid(options.len() as isize) as usize]
+}
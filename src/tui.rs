@@ -1,11 +1,15 @@
 use crate::app::{App, AppResult};
 use crate::event::EventsPublisher;
+use crate::icons::{self, GraphicsProtocol};
 use crate::ui;
+use crossterm::cursor::MoveTo;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::style::Print;
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::Backend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
-use std::io;
+use std::io::{self, Write};
 use std::panic;
 
 /// Representation of a terminal user interface.
@@ -18,12 +22,16 @@ pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventsPublisher,
+    /// Icon drawn over the last frame and where, to redraw it only when it changes.
+    placed_icon: Option<(String, Rect)>,
+    /// Terminal size the icon was drawn at.
+    placed_size: Rect,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventsPublisher) -> Self {
-        Self { terminal, events}
+        Self { terminal, events, placed_icon: None, placed_size: Rect::default() }
     }
 
     /// Initializes the terminal interface.
@@ -58,10 +66,46 @@ impl<B: Backend> Tui<B> {
         }
         
         // Draw the interface - remove the generic parameter B
-        self.terminal.draw(|frame| {
+        let size = self.terminal.draw(|frame| {
             ui::render(app, frame);  // No generic parameter here!
-        })?;
+        })?.area;
         
+        self.draw_icon(app, size)
+    }
+
+    /// Draws the icon placed by the last render with kitty or sixel graphics.
+    ///
+    /// The cells below are left unchanged between frames, so the image is only sent again when
+    /// it, its position or the terminal size changes.
+    fn draw_icon(&mut self, app: &mut App, size: Rect) -> AppResult<()> {
+        let wanted = app.icon_placement.as_ref().map(|(icon, area)| (icon.code.clone(), *area));
+        if wanted == self.placed_icon && size == self.placed_size {
+            return Ok(());
+        }
+        let mut out = io::stderr();
+        if self.placed_icon.is_some() {
+            match app.icon_protocol {
+                GraphicsProtocol::Kitty => crossterm::queue!(out, Print(icons::KITTY_CLEAR))?,
+                // Sixels stay until text covers them, so have everything redrawn
+                _ => {
+                    self.terminal.clear()?;
+                    self.terminal.draw(|frame| ui::render(app, frame))?;
+                }
+            }
+        }
+        if let Some((icon, area)) = &app.icon_placement {
+            let sequence = match app.icon_protocol {
+                GraphicsProtocol::Kitty => icons::kitty_sequence(icon, area.width, area.height),
+                _ => {
+                    let (cell_width, cell_height) = cell_pixels();
+                    icons::sixel_sequence(icon, u32::from(area.width) * cell_width, u32::from(area.height) * cell_height)
+                }
+            };
+            crossterm::queue!(out, MoveTo(area.x, area.y), Print(sequence))?;
+        }
+        out.flush()?;
+        self.placed_icon = wanted;
+        self.placed_size = size;
         Ok(())
     }
 
@@ -84,3 +128,14 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 }
+
+/// Size of a terminal cell in pixels, guessing 8x16 when the terminal does not say.
+fn cell_pixels() -> (u32, u32) {
+    match terminal::window_size() {
+        Ok(size) if size.columns > 0 && size.rows > 0 && size.width > 0 && size.height > 0 => (
+            u32::from(size.width / size.columns),
+            u32::from(size.height / size.rows),
+        ),
+        _ => (8, 16),
+    }
+}
@@ -1,18 +1,50 @@
 use ratatui::Frame;
-use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Direction, Layout, Alignment, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Clear, Sparkline};
+use std::sync::Arc;
+use chrono::Utc;
+use ratatui::symbols;
+use ratatui::widgets::{
+    Axis, Block, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, List, ListItem, Paragraph, Row,
+    Sparkline, Table, Wrap,
+};
+use ratatui::widgets::block::{Position, Title};
 use unicode_width::UnicodeWidthStr;
-use crate::app::{App, InputMode};
+use crate::app::{App, InputMode, WeatherState};
+use crate::cities::CityRow;
+use crate::connection::{
+    uv_category, AirQuality, AlertSeverity, NetworkStatus, Nowcast, ProviderKind, RainOutlook, WeatherAlert,
+};
+use crate::i18n::{fill, Language, Messages};
+use crate::icons::{glyph, GraphicsProtocol, HalfBlockIcon, Icon};
+use crate::setup::SetupStep;
+use crate::units::UnitSystem;
+
+/// Cells the condition icon takes up; twice as wide as high, so it comes out square.
+const ICON_SIZE: (u16, u16) = (10, 5);
 
 /// Renders the complete user interface.
 pub fn render(app: &mut App, frame: &mut Frame) {
+    // The wizard replaces the dashboard until it is done
+    if app.input_mode == InputMode::Setup {
+        app.icon_placement = None;
+        render_setup(app, frame);
+        return;
+    }
+    
+    let mut icon_placement = None;
+    let messages = app.messages();
+    let locale = app.locale.clone();
+    
     // Main dashboard block with title and border
     let main_block = Block::default()
         .borders(Borders::ALL)
-        .title(" Weather CLI Dashboard ")
+        .title(if app.offline {
+            format!(" {} {} ", messages.app_title, messages.offline_tag)
+        } else {
+            format!(" {} ", messages.app_title)
+        })
         .title_alignment(Alignment::Center)
         .border_style(Style::default().fg(Color::Cyan));
     let inner_area = main_block.inner(frame.size());
@@ -24,41 +56,103 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .constraints([
             Constraint::Length(8),  // cities section
             Constraint::Min(10),    // weather details section
+            Constraint::Length(1),  // status line
         ].as_ref())
         .split(inner_area);
     
+    render_status_line(app, frame, chunks[2]);
+    
     // --- Cities Block ---
     let cities_block = Block::default()
         .borders(Borders::ALL)
-        .title(" Cities ")
+        .title(messages.cities_title)
         .title_alignment(Alignment::Center)
         .border_style(Style::default().fg(Color::Magenta));
     let cities_area = cities_block.inner(chunks[0]);
     frame.render_widget(cities_block, chunks[0]);
 
-    let cities: Vec<ListItem> = app.cities
-        .iter()
+    let units = app.units();
+    let selected_row = app.selected_row();
+    let cities: Vec<ListItem> = app.city_rows()
+        .into_iter()
         .enumerate()
-        .map(|(i, city)| {
-            let is_selected = i == app.selected_city;
+        .map(|(row, line)| {
+            let is_selected = selected_row == Some(row);
             let prefix = if is_selected { "➤ " } else { "  " };
-            ListItem::new(format!("{}{}", prefix, city))
-                .style(Style::default().fg(if is_selected { Color::Yellow } else { Color::White }))
+            let i = match line {
+                CityRow::City(i) => i,
+                CityRow::Group(group) => {
+                    let group = &app.groups[group];
+                    let count = app.cities
+                        .iter()
+                        .filter(|city| !city.pinned && city.group.as_ref() == Some(&group.name))
+                        .count();
+                    let fold = if group.collapsed { "▸" } else { "▾" };
+                    return ListItem::new(format!("{}{} {} ({})", prefix, fold, group.name, count))
+                        .style(Style::default()
+                            .fg(if is_selected { Color::Yellow } else { Color::Magenta })
+                            .add_modifier(Modifier::BOLD));
+                }
+            };
+            let city = &app.cities[i];
+            // Pinned cities are marked, grouped ones indented under their header
+            let marker = if city.pinned { "★ " } else if city.group.is_some() { "  " } else { "" };
+            let weather = app.weather_for(city).map(|data| &data.weather);
+            let temperature = weather
+                .map(|weather| units.temperature(weather.temperature))
+                .unwrap_or_default();
+            // Only with a known time zone; a UTC clock would be misleading here
+            let clock = weather
+                .filter(|weather| weather.utc_offset.is_some())
+                .map(|weather| locale.time(&weather.local_time(&Utc::now())))
+                .unwrap_or_default();
+            let alert = match app.active_alerts(city).first() {
+                Some(alert) => Span::styled(" ⚠", Style::default().fg(severity_color(alert.severity()))),
+                None => Span::raw(""),
+            };
+            let status = match app.weather_state(city) {
+                WeatherState::Loading => Span::styled(format!(" {}", app.spinner()), Style::default().fg(Color::Cyan)),
+                WeatherState::Failed(_) => Span::styled(" !", Style::default().fg(Color::Red)),
+                WeatherState::NotLoaded | WeatherState::Loaded(_) => Span::raw(""),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(
+                    "{}{}{:<width$} {:>8} {:>8}",
+                    prefix, marker, city.label(), temperature, clock, width = 24 - marker.chars().count(),
+                )),
+                alert,
+                status,
+            ]))
+            .style(Style::default().fg(if is_selected { Color::Yellow } else { Color::White }))
         })
         .collect();
     let list_component = List::new(cities)
         .highlight_style(Style::default().add_modifier(Modifier::BOLD))
         .highlight_symbol(">> ");
-    frame.render_stateful_widget(list_component, cities_area, &mut app.list_state());
+    let mut list_state = app.list_state();
+    frame.render_stateful_widget(list_component, cities_area, &mut list_state);
+    app.city_list_area = Some((cities_area, list_state.offset()));
 
     // --- Weather Details Block ---
     let weather_block = Block::default()
         .borders(Borders::ALL)
-        .title(" Weather Details ")
+        .title(fill(messages.details_title, &[&app.provider.name()]))
         .title_alignment(Alignment::Center)
         .border_style(Style::default().fg(Color::Green));
-    let weather_area = weather_block.inner(chunks[1]);
+    let mut weather_area = weather_block.inner(chunks[1]);
     frame.render_widget(weather_block, chunks[1]);
+    
+    // Active alerts get a banner across the top of the details
+    let alerts = app.selected_alerts();
+    if let Some(alert) = alerts.first() {
+        let banner_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+            .split(weather_area);
+        let until = locale.weekday_time(&alert.end.with_timezone(&app.selected_timezone()));
+        render_alert_banner(alert, &until, alerts.len() - 1, messages, frame, banner_chunks[0]);
+        weather_area = banner_chunks[1];
+    }
 
     // Divide the weather area into 70% text and 30% graph
     let weather_chunks = Layout::default()
@@ -69,93 +163,650 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         ].as_ref())
         .split(weather_area);
 
-    // Build weather details text (if available)
-    let weather_text = if let Some(weather) = &app.current_weather {
+    // Build weather details text (if available); a failed fetch hides older data
+    let selected = app.selected_weather().filter(|_| app.selected_error().is_none());
+    let weather_text = if let Some(data) = selected {
+        let weather = &data.weather;
+        let stale_line = match data.stale_since {
+            Some(fetched_at) => Line::from(Span::styled(
+                format!(
+                    "{}{}",
+                    fill(messages.stale_since, &[&locale.date_time(&fetched_at)]),
+                    if app.offline { messages.offline_suffix } else { "" }
+                ),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            None => Line::raw(""),
+        };
         Text::from(vec![
             Line::from(vec![
-                Span::styled("City: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(format!("{}, {}", weather.name, weather.country)),
+                Span::styled(messages.city, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(
+                    "{}, {} ({} {})",
+                    weather.name, weather.country,
+                    locale.time(&weather.local_time(&Utc::now())), weather.timezone_label()
+                )),
             ]),
-            Line::raw(""),
+            stale_line,
             Line::from(vec![
-                Span::styled("Temp: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(format!("{:.1}°C (feels like {:.1}°C)", weather.temperature, weather.feels_like)),
+                Span::styled(messages.temp, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(
+                    "{} ({} {})",
+                    units.temperature(weather.temperature), messages.feels_like, units.temperature(weather.feels_like)
+                )),
             ]),
             Line::from(vec![
-                Span::styled("Range: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(format!("{:.1}°C - {:.1}°C", weather.temp_min, weather.temp_max)),
+                Span::styled(messages.range, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("{} - {}", units.temperature(weather.temp_min), units.temperature(weather.temp_max))),
             ]),
             Line::from(vec![
-                Span::styled("Conditions: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(messages.conditions, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
                 Span::raw(format!("{} ({})", weather.weather_main, weather.description)),
             ]),
             Line::raw(""),
             Line::from(vec![
-                Span::styled("Humidity: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(messages.humidity, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
                 Span::raw(format!("{}%", weather.humidity)),
             ]),
             Line::from(vec![
-                Span::styled("Wind: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(format!("{:.1} m/s", weather.wind_speed)),
+                Span::styled(messages.wind, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw({
+                    let direction = weather.wind_direction.map_or("–".to_string(), |degrees| format!("{}°", degrees));
+                    match weather.wind_gust {
+                        Some(gust) => format!(
+                            "{} {} ({} {})",
+                            units.speed(weather.wind_speed), direction, messages.gusts, units.speed(gust)
+                        ),
+                        None => format!("{} {}", units.speed(weather.wind_speed), direction),
+                    }
+                }),
+            ]),
+            Line::from(vec![
+                Span::styled(messages.visibility, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(match weather.visibility {
+                    Some(meters) => units.distance(f64::from(meters)),
+                    None => messages.not_available.to_string(),
+                }),
+            ]),
+            Line::from(vec![
+                Span::styled(messages.precipitation, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(match (weather.rain_1h, weather.snow_1h) {
+                    (Some(rain), Some(snow)) => format!(
+                        "{} {}, {} {} {}",
+                        messages.rain, units.precipitation(rain),
+                        messages.snow, units.precipitation(snow),
+                        messages.last_hour
+                    ),
+                    (Some(rain), None) => format!("{} {} {}", messages.rain, units.precipitation(rain), messages.last_hour),
+                    (None, Some(snow)) => format!("{} {} {}", messages.snow, units.precipitation(snow), messages.last_hour),
+                    (None, None) => messages.none.to_string(),
+                }),
             ]),
             Line::from(vec![
-                Span::styled("Pressure: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(format!("{} hPa", weather.pressure)),
+                Span::styled(messages.pressure, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(units.pressure(f64::from(weather.pressure))),
             ]),
             Line::raw(""),
             Line::from(vec![
-                Span::styled("Sunrise: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(messages.sunrise, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
                 Span::raw(match weather.sunrise {
-                    Some(time) => format!("{}", time.format("%H:%M")),
-                    None => "N/A".to_string(),
+                    Some(time) => locale.time(&weather.local_time(&time)),
+                    None => messages.not_available.to_string(),
                 }),
             ]),
             Line::from(vec![
-                Span::styled("Sunset: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(messages.sunset, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
                 Span::raw(match weather.sunset {
-                    Some(time) => format!("{}", time.format("%H:%M")),
-                    None => "N/A".to_string(),
+                    Some(time) => locale.time(&weather.local_time(&time)),
+                    None => messages.not_available.to_string(),
                 }),
             ]),
         ])
+    } else if let Some(error) = app.selected_error() {
+        Text::from(vec![
+            Line::from(vec![
+                Span::styled(messages.error, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::styled(error.message(messages), Style::default().fg(Color::Red)),
+            ]),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled(messages.hint, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::raw(error.suggestion(messages)),
+            ]),
+        ])
     } else {
         Text::from(vec![
-            Line::raw("No weather data available"),
-            Line::raw("Press Enter to fetch weather"),
+            Line::raw(messages.no_data),
+            Line::raw(messages.no_data_hint),
         ])
     };
 
     let weather_info = Paragraph::new(weather_text)
+        .wrap(Wrap { trim: true })
         .alignment(Alignment::Left)
         .style(Style::default().fg(Color::White));
-    frame.render_widget(weather_info, weather_chunks[0]);
+    
+    // Put the air quality panel to the right of the details once we have data
+    if let Some(data) = selected {
+        let detail_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(55),
+                Constraint::Percentage(45),
+            ].as_ref())
+            .split(weather_chunks[0]);
+        let text_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(ICON_SIZE.0 + 1),
+                Constraint::Min(0),
+            ].as_ref())
+            .split(detail_chunks[0]);
+        let icon = app.icon(&data.weather.icon).cloned();
+        icon_placement = render_icon(icon, &data.weather.icon, app.icon_protocol, frame, text_chunks[0]);
+        frame.render_widget(weather_info, text_chunks[1]);
+        render_air_quality(data.air_quality.as_ref(), messages, frame, detail_chunks[1]);
+    } else {
+        frame.render_widget(weather_info, weather_chunks[0]);
+    }
 
     // Render sparkline graph (if hourly data exists) in the lower weather area.
-    if let Some(weather) = &app.current_weather {
-        // Convert f64 temperatures to u64 values for the sparkline.
-        let sparkline_data: Vec<u64> = weather.hourly_temps.clone().unwrap_or_default()
-            .iter().map(|&temp| temp.round() as u64).collect();
+    if let Some(data) = selected {
+        let weather = &data.weather;
+        let temps: Vec<f64> = weather.hourly_temps.clone().unwrap_or_default()
+            .into_iter().map(|temp| units.temperature_value(temp)).collect();
+        // The sparkline only takes unsigned values, so plot the offset from the coldest step.
+        let coldest = temps.iter().cloned().fold(f64::INFINITY, f64::min);
+        let warmest = temps.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let sparkline_data: Vec<u64> = temps.iter()
+            .map(|&temp| ((temp - coldest).round() as u64) + 1)
+            .collect();
+        let title = match &data.forecast {
+            Some(forecast) if !temps.is_empty() => {
+                let last = &forecast.entries[temps.len() - 1];
+                let unit = units.temperature_symbol();
+                fill(messages.next_hours, &[
+                    &format!("{:.0}{}", coldest, unit),
+                    &format!("{:.0}{}", warmest, unit),
+                    &locale.weekday_time(&weather.local_time(&last.time)),
+                ])
+            }
+            _ => messages.forecast_unavailable.to_string(),
+        };
             
         let sparkline = Sparkline::default()
-            .block(Block::default().title("Next Hours").borders(Borders::ALL))
+            .block(Block::default().title(title).borders(Borders::ALL))
             .data(&sparkline_data)
-            .style(Style::default().fg(Color::Green))
-            .max(40);
-        frame.render_widget(sparkline, weather_chunks[1]);
+            .style(Style::default().fg(Color::Green));
+        // Same split as above, so the nowcast sits under the air quality panel
+        let graph_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(55),
+                Constraint::Percentage(45),
+            ].as_ref())
+            .split(weather_chunks[1]);
+        frame.render_widget(sparkline, graph_chunks[0]);
+        render_nowcast(data.nowcast.as_ref(), messages, frame, graph_chunks[1]);
     }
 
-    // Render input popup if in editing mode.
-    if app.input_mode == InputMode::Editing {
-        render_input_popup(app, frame);
+    // Images drawn over the frame would cover popups, so they are only placed without one
+    app.icon_placement = icon_placement.filter(|_| app.input_mode == InputMode::Normal);
+    
+    // Render the popup of the current mode, if any.
+    match app.input_mode {
+        InputMode::Normal => {}
+        InputMode::Editing | InputMode::Selecting | InputMode::Grouping | InputMode::Renaming => {
+            render_input_popup(app, frame)
+        }
+        InputMode::Alerts => render_alerts_popup(app, frame),
+        InputMode::History => render_history_popup(app, frame),
+        InputMode::Setup => {}
     }
 }
 
-/// Renders the input popup for adding a new city.
+/// Renders the condition icon at the top of `area`, or its glyph when there is no image.
+///
+/// For overlay protocols nothing is drawn; the placement is returned for the terminal to draw afterwards.
+fn render_icon(
+    icon: Option<Arc<Icon>>,
+    code: &str,
+    protocol: GraphicsProtocol,
+    frame: &mut Frame,
+    area: Rect,
+) -> Option<(Arc<Icon>, Rect)> {
+    let icon_area = Rect {
+        width: ICON_SIZE.0.min(area.width),
+        height: ICON_SIZE.1.min(area.height),
+        ..area
+    };
+    match (icon, protocol) {
+        (Some(icon), GraphicsProtocol::HalfBlocks) => {
+            frame.render_widget(HalfBlockIcon::new(&icon), icon_area);
+            None
+        }
+        (Some(icon), protocol) if protocol.is_overlay() => Some((icon, icon_area)),
+        _ => {
+            let glyph_area = Rect { y: icon_area.y + icon_area.height / 2, height: 1, ..icon_area };
+            frame.render_widget(
+                Paragraph::new(glyph(code))
+                    .alignment(Alignment::Center)
+                    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                glyph_area,
+            );
+            None
+        }
+    }
+}
+
+/// Colour for an alert severity.
+fn severity_color(severity: AlertSeverity) -> Color {
+    match severity {
+        AlertSeverity::Warning => Color::Red,
+        AlertSeverity::Watch => Color::LightRed,
+        AlertSeverity::Advisory => Color::Yellow,
+    }
+}
+
+/// Renders a one-line banner for the most severe active alert.
+fn render_alert_banner(
+    alert: &WeatherAlert,
+    until: &str,
+    others: usize,
+    messages: &Messages,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let mut text = fill(messages.alert_banner, &[&alert.event, &until]);
+    if others > 0 {
+        text.push_str(&fill(messages.alert_more, &[&others]));
+    }
+    text.push_str(messages.alert_details_hint);
+    let banner = Paragraph::new(text).style(
+        Style::default().fg(Color::Black).bg(severity_color(alert.severity())).add_modifier(Modifier::BOLD),
+    );
+    frame.render_widget(banner, area);
+}
+
+/// Renders the popup listing every active alert of the selected city in full.
+fn render_alerts_popup(app: &App, frame: &mut Frame) {
+    let messages = app.messages();
+    let area = centered_rect(80, 70, frame.size());
+    let zone = app.selected_timezone();
+    
+    let mut lines = Vec::new();
+    for alert in app.selected_alerts() {
+        let label = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        lines.push(Line::from(Span::styled(
+            alert.event.clone(),
+            Style::default().fg(severity_color(alert.severity())).add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(vec![
+            Span::styled(messages.alert_sender, label),
+            Span::raw(alert.sender.clone()),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(messages.alert_window, label),
+            Span::raw(format!(
+                "{} - {}",
+                app.locale.date_time(&alert.start.with_timezone(&zone)),
+                app.locale.date_time(&alert.end.with_timezone(&zone)),
+            )),
+        ]));
+        lines.push(Line::raw(""));
+        lines.extend(alert.description.lines().map(|line| Line::raw(line.to_string())));
+        lines.push(Line::raw(""));
+    }
+    
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(messages.alerts_title)
+                .title(Title::from(messages.alerts_close_hint).position(Position::Bottom))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.alert_scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Renders the bottom line: a failed settings save or network retry state when there is any, key hints otherwise.
+fn render_status_line(app: &App, frame: &mut Frame, area: Rect) {
+    let messages = app.messages();
+    let line = match app.http.status() {
+        Some(NetworkStatus::Retrying { host, attempt, max_retries, until, reason }) => {
+            let wait = until.saturating_duration_since(std::time::Instant::now());
+            Line::from(Span::styled(
+                fill(messages.retrying, &[
+                    &host, &attempt, &max_retries, &format!("{:.1}", wait.as_secs_f64()), &reason,
+                ]),
+                Style::default().fg(Color::Yellow),
+            ))
+        }
+        Some(NetworkStatus::Recovered { host, retries }) => Line::from(Span::styled(
+            fill(messages.recovered, &[&host, &retries]),
+            Style::default().fg(Color::Green),
+        )),
+        Some(NetworkStatus::GaveUp { host, retries, reason }) => Line::from(Span::styled(
+            fill(messages.gave_up, &[&host, &retries, &reason]),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(Span::styled(
+            messages.key_hints,
+            Style::default().fg(Color::DarkGray),
+        )),
+    };
+    // Feedback on the last key press goes over the network state
+    let line = match &app.toast {
+        Some((toast, _)) => Line::from(Span::styled(toast.clone(), Style::default().fg(Color::White))),
+        None => line,
+    };
+    // Settings that were not saved would be lost on exit, which matters more than the network
+    let line = match &app.config_error {
+        Some(error) => Line::from(Span::styled(
+            fill(messages.config_save_failed, &[error]),
+            Style::default().fg(Color::Red),
+        )),
+        None => line,
+    };
+    
+    let Some(countdown) = app.refresh_countdown() else {
+        frame.render_widget(Paragraph::new(line), area);
+        return;
+    };
+    let secs = countdown.as_secs();
+    let countdown = fill(messages.next_refresh, &[&format!("{}:{:02}", secs / 60, secs % 60)]);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(countdown.width() as u16 + 1),
+        ].as_ref())
+        .split(area);
+    frame.render_widget(Paragraph::new(line), chunks[0]);
+    frame.render_widget(
+        Paragraph::new(countdown).alignment(Alignment::Right).style(Style::default().fg(Color::Cyan)),
+        chunks[1],
+    );
+}
+
+/// Colour for an air quality level, from green (1) to magenta (5).
+fn aqi_color(aqi: u8) -> Color {
+    match aqi {
+        1 => Color::Green,
+        2 => Color::LightGreen,
+        3 => Color::Yellow,
+        4 => Color::LightRed,
+        _ => Color::Magenta,
+    }
+}
+
+/// Renders the AQI gauge, the pollutant breakdown and the hourly AQI outlook.
+fn render_air_quality(air_quality: Option<&AirQuality>, messages: &Messages, frame: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(messages.air_quality_title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Blue));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    
+    let Some(air_quality) = air_quality else {
+        frame.render_widget(Paragraph::new(messages.air_quality_unavailable), inner);
+        return;
+    };
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // gauge
+            Constraint::Length(1), // spacer
+            Constraint::Length(6), // components
+            Constraint::Min(0),    // hourly outlook
+        ].as_ref())
+        .split(inner);
+    
+    let aqi = air_quality.current.aqi;
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(aqi_color(aqi)).bg(Color::DarkGray))
+        .ratio(f64::from(aqi.clamp(1, 5)) / 5.0)
+        .label(format!("AQI {} - {}", aqi, messages.aqi_label(aqi)));
+    frame.render_widget(gauge, chunks[0]);
+    
+    let components: Vec<Line> = air_quality.current.components.levels()
+        .iter()
+        .map(|&(name, value, level)| Line::from(vec![
+            Span::styled(format!("{:<6}", name), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("{:>8.1} μg/m³", value), Style::default().fg(aqi_color(level))),
+        ]))
+        .collect();
+    frame.render_widget(Paragraph::new(components), chunks[2]);
+    
+    if !air_quality.forecast.is_empty() {
+        let outlook: Vec<u64> = air_quality.forecast.iter()
+            .take(chunks[3].width as usize)
+            .map(|reading| u64::from(reading.aqi))
+            .collect();
+        let worst = outlook.iter().copied().max().unwrap_or(1) as u8;
+        let sparkline = Sparkline::default()
+            .block(Block::default().title(messages.hourly_aqi).borders(Borders::TOP))
+            .data(&outlook)
+            .max(5)
+            .style(Style::default().fg(aqi_color(worst)));
+        frame.render_widget(sparkline, chunks[3]);
+    }
+}
+
+/// Colour of a WHO UV exposure category, from green (low) to violet (extreme).
+fn uv_color(uv_index: f64) -> Color {
+    match uv_category(uv_index) {
+        0 => Color::Green,
+        1 => Color::Yellow,
+        2 => Color::LightRed,
+        3 => Color::Red,
+        _ => Color::Magenta,
+    }
+}
+
+/// Bar and colour for one minute of the precipitation strip, by rate in mm/h.
+fn precipitation_cell(intensity: Option<f64>) -> Span<'static> {
+    let (symbol, color) = match intensity {
+        None => (" ", Color::Reset),
+        Some(rate) if rate < Nowcast::RAIN_THRESHOLD => ("·", Color::DarkGray),
+        Some(rate) if rate < 2.5 => ("▂", Color::LightBlue),
+        Some(rate) if rate < 10.0 => ("▄", Color::Blue),
+        Some(rate) if rate < 50.0 => ("▆", Color::LightMagenta),
+        Some(_) => ("█", Color::Magenta),
+    };
+    Span::styled(symbol, Style::default().fg(color))
+}
+
+/// Renders the UV gauge, the 60-minute precipitation strip and when rain starts or stops.
+fn render_nowcast(nowcast: Option<&Nowcast>, messages: &Messages, frame: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(messages.nowcast_title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Blue));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    
+    let Some(nowcast) = nowcast else {
+        frame.render_widget(Paragraph::new(messages.nowcast_unavailable), inner);
+        return;
+    };
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // UV gauge
+            Constraint::Length(1), // precipitation strip
+            Constraint::Min(0),    // rain summary
+        ].as_ref())
+        .split(inner);
+    
+    match nowcast.uv_index {
+        Some(uv_index) => {
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(uv_color(uv_index)).bg(Color::DarkGray))
+                .ratio((uv_index / 11.0).clamp(0.0, 1.0))
+                .label(format!("{} {:.1} - {}", messages.uv_index, uv_index, messages.uv_label(uv_index)));
+            frame.render_widget(gauge, chunks[0]);
+        }
+        None => {
+            let line = format!("{} {}", messages.uv_index, messages.not_available);
+            frame.render_widget(Paragraph::new(line), chunks[0]);
+        }
+    }
+    
+    let now = Utc::now();
+    let Some(outlook) = nowcast.rain_outlook(now) else {
+        frame.render_widget(Paragraph::new(messages.minutely_unavailable), chunks[1]);
+        return;
+    };
+    // One column per minute; narrower panels show every n-th minute
+    let minutes = nowcast.next_hour(now);
+    let columns = usize::from(chunks[1].width).clamp(1, minutes.len());
+    let strip: Vec<Span> = (0..columns)
+        .map(|column| precipitation_cell(minutes[column * minutes.len() / columns]))
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(strip)), chunks[1]);
+    
+    let summary = match outlook {
+        RainOutlook::Dry => messages.rain_dry.to_string(),
+        RainOutlook::StartsIn(minutes) => fill(messages.rain_starts, &[&minutes]),
+        RainOutlook::StopsIn(minutes) => fill(messages.rain_stops, &[&minutes]),
+        RainOutlook::Ongoing => messages.rain_ongoing.to_string(),
+    };
+    let color = if outlook == RainOutlook::Dry { Color::Green } else { Color::LightBlue };
+    frame.render_widget(Paragraph::new(summary).style(Style::default().fg(color)), chunks[2]);
+}
+
+/// Renders the history view: the picked day, a temperature chart and the hourly table.
+fn render_history_popup(app: &App, frame: &mut Frame) {
+    let messages = app.messages();
+    let units = app.units();
+    let area = centered_rect(90, 85, frame.size());
+    let city = app.cities.get(app.selected_city).map(|city| city.label()).unwrap_or_default();
+    
+    let block = Block::default()
+        .title(fill(messages.history_title, &[&city]))
+        .title(Title::from(messages.history_hint).position(Position::Bottom))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),      // date picker
+            Constraint::Length(1),      // spacer
+            Constraint::Percentage(45), // chart
+            Constraint::Min(3),         // table
+        ].as_ref())
+        .split(inner);
+    
+    let date_line = Line::from(vec![
+        Span::raw("◀ "),
+        Span::styled(app.locale.date(&app.history_date), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        Span::raw(" ▶"),
+    ]);
+    frame.render_widget(Paragraph::new(date_line).alignment(Alignment::Center), chunks[0]);
+    
+    let (history, observations) = match (&app.history_state, &app.history) {
+        (_, Some(history)) if !history.observations.is_empty() => (history, &history.observations),
+        (WeatherState::Loading, _) => {
+            let text = format!("{} {}", app.spinner(), messages.history_loading);
+            frame.render_widget(Paragraph::new(text), chunks[2]);
+            return;
+        }
+        (WeatherState::Failed(error), _) => {
+            let text = vec![
+                Line::from(vec![
+                    Span::styled(messages.error, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::styled(error.message(messages), Style::default().fg(Color::Red)),
+                ]),
+                Line::from(vec![
+                    Span::styled(messages.hint, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                    Span::raw(error.suggestion(messages)),
+                ]),
+            ];
+            frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), chunks[2]);
+            return;
+        }
+        (WeatherState::Loaded(_), _) => {
+            frame.render_widget(Paragraph::new(messages.history_empty), chunks[2]);
+            return;
+        }
+        _ => return,
+    };
+    
+    // Temperature chart, one point per hour
+    let points: Vec<(f64, f64)> = observations.iter()
+        .enumerate()
+        .map(|(i, observation)| (i as f64, units.temperature_value(observation.temperature)))
+        .collect();
+    let coldest = points.iter().map(|&(_, temp)| temp).fold(f64::INFINITY, f64::min);
+    let warmest = points.iter().map(|&(_, temp)| temp).fold(f64::NEG_INFINITY, f64::max);
+    let last = observations.len() - 1;
+    let x_labels: Vec<Span> = [0, last / 2, last]
+        .iter()
+        .map(|&i| Span::raw(app.locale.time(&history.local_time(&observations[i].time))))
+        .collect();
+    let y_labels = vec![
+        Span::raw(format!("{:.0}{}", coldest.floor() - 1.0, units.temperature_symbol())),
+        Span::raw(format!("{:.0}{}", warmest.ceil() + 1.0, units.temperature_symbol())),
+    ];
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Color::Green))
+        .data(&points);
+    let chart = Chart::new(vec![dataset])
+        .x_axis(Axis::default().bounds([0.0, last.max(1) as f64]).labels(x_labels))
+        .y_axis(Axis::default().bounds([coldest.floor() - 1.0, warmest.ceil() + 1.0]).labels(y_labels));
+    frame.render_widget(chart, chunks[2]);
+    
+    // Hourly table
+    let header = Row::new(messages.history_columns.iter().map(|&title| Cell::from(title)))
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows = observations.iter().map(|observation| Row::new(vec![
+        app.locale.time(&history.local_time(&observation.time)),
+        units.temperature(observation.temperature),
+        observation.description.clone(),
+        units.precipitation(observation.precipitation),
+        units.speed(observation.wind_speed),
+        format!("{}%", observation.humidity),
+    ]));
+    let table = Table::new(rows, [
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Min(16),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(9),
+    ])
+    .header(header)
+    .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(table, chunks[3]);
+}
+
+/// Renders the input popup for adding a new city, naming a group or renaming a city.
+///
+/// Below the input it shows either the lookup status or the candidates to pick from.
 fn render_input_popup(app: &App, frame: &mut Frame) {
-    let area = centered_rect(60, 20, frame.size());
+    let messages = app.messages();
+    let area = centered_rect(60, 40, frame.size());
+    let (title, hint) = match app.input_mode {
+        InputMode::Grouping => (messages.group_title, messages.group_hint),
+        InputMode::Renaming => (messages.rename_title, messages.rename_hint),
+        _ => (messages.add_city_title, messages.add_city_hint),
+    };
     
     let input_block = Block::default()
-        .title(" Add City ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow));
     
@@ -163,15 +814,156 @@ fn render_input_popup(app: &App, frame: &mut Frame) {
     frame.render_widget(Clear, area); // Clear the popup area first
     frame.render_widget(input_block, area);
     
+    let popup_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // typed text
+            Constraint::Length(1), // spacer
+            Constraint::Min(1),    // status or candidates
+        ].as_ref())
+        .split(input_area);
+    
     let input_text = Paragraph::new(Text::from(app.input.as_str()))
         .style(Style::default().fg(Color::White));
-    frame.render_widget(input_text, input_area);
+    frame.render_widget(input_text, popup_chunks[0]);
+    
+    if app.input_mode == InputMode::Selecting {
+        let candidates: Vec<ListItem> = app.candidates
+            .iter()
+            .map(|place| ListItem::new(place.describe()))
+            .collect();
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(Some(app.selected_candidate));
+        let list_component = List::new(candidates)
+            .block(Block::default().title(messages.candidates_hint).borders(Borders::TOP))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .highlight_symbol("➤ ");
+        frame.render_stateful_widget(list_component, popup_chunks[2], &mut state);
+    } else {
+        let status = app.input_status.as_deref().unwrap_or(hint);
+        let status_text = Paragraph::new(status)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::Gray));
+        frame.render_widget(status_text, popup_chunks[2]);
+        
+        // Position the cursor within the input area.
+        frame.set_cursor(
+            popup_chunks[0].x + UnicodeWidthStr::width(app.input.as_str()) as u16,
+            popup_chunks[0].y,
+        );
+    }
+}
+
+/// Renders the current page of the first-run wizard over the whole frame.
+///
+/// Choice pages list their options; text pages show the input and below it the status or the places to pick from.
+fn render_setup(app: &App, frame: &mut Frame) {
+    let messages = app.messages();
+    let setup = &app.setup;
+    let (step, steps) = setup.progress();
+    
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(messages.setup_title)
+        .title_alignment(Alignment::Center)
+        .title(Title::from(fill(messages.setup_step, &[&step, &steps])).position(Position::Bottom))
+        .border_style(Style::default().fg(Color::Cyan));
+    let area = centered_rect(70, 60, block.inner(frame.size()));
+    frame.render_widget(block, frame.size());
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // question
+            Constraint::Length(1), // spacer
+            Constraint::Min(1),    // options or input
+            Constraint::Length(1), // key hints
+        ].as_ref())
+        .split(area);
+    
+    let (question, options, selected) = match setup.step {
+        SetupStep::Provider => (
+            messages.setup_provider,
+            ProviderKind::ALL
+                .iter()
+                .map(|kind| {
+                    let note = if kind.needs_api_key() { messages.setup_needs_key } else { messages.setup_no_key };
+                    format!("{} ({})", kind.label(), note)
+                })
+                .collect(),
+            ProviderKind::ALL.iter().position(|&kind| kind == setup.provider),
+        ),
+        SetupStep::Units => (
+            messages.setup_units,
+            messages.unit_systems.iter().map(|name| name.to_string()).collect(),
+            UnitSystem::ALL.iter().position(|&system| system == setup.units),
+        ),
+        SetupStep::Language => (
+            messages.setup_language,
+            Language::ALL.iter().map(|language| language.native_name().to_string()).collect(),
+            Language::ALL.iter().position(|&language| language == setup.language),
+        ),
+        SetupStep::ApiKey => (messages.setup_api_key, Vec::new(), None),
+        SetupStep::HomeCity if !app.candidates.is_empty() => (
+            messages.setup_home_city,
+            app.candidates.iter().map(|place| place.describe()).collect(),
+            Some(app.selected_candidate),
+        ),
+        SetupStep::HomeCity => (messages.setup_home_city, Vec::new(), None),
+    };
     
-    // Position the cursor within the input area.
-    frame.set_cursor(
-        input_area.x + UnicodeWidthStr::width(app.input.as_str()) as u16,
-        input_area.y,
+    frame.render_widget(
+        Paragraph::new(question)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        chunks[0],
     );
+    
+    let hint = if setup.step.takes_text() && app.candidates.is_empty() {
+        messages.setup_input_hint
+    } else {
+        messages.setup_choose_hint
+    };
+    frame.render_widget(Paragraph::new(hint).style(Style::default().fg(Color::Gray)), chunks[3]);
+    
+    if !options.is_empty() {
+        let items: Vec<ListItem> = options.into_iter().map(ListItem::new).collect();
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(selected);
+        let list = List::new(items)
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .highlight_symbol("➤ ");
+        frame.render_stateful_widget(list, chunks[2], &mut state);
+        return;
+    }
+    
+    let input_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // typed text
+            Constraint::Length(1), // spacer
+            Constraint::Min(1),    // status
+        ].as_ref())
+        .split(chunks[2]);
+    
+    // Only the end of the key is shown, enough to spot a bad paste
+    let input = if setup.step == SetupStep::ApiKey {
+        let shown = app.input.chars().count().saturating_sub(4);
+        app.input.chars().enumerate().map(|(i, c)| if i < shown { '•' } else { c }).collect()
+    } else {
+        app.input.clone()
+    };
+    frame.set_cursor(input_chunks[0].x + UnicodeWidthStr::width(input.as_str()) as u16, input_chunks[0].y);
+    frame.render_widget(
+        Paragraph::new(input).style(Style::default().fg(Color::White)),
+        input_chunks[0],
+    );
+    if let Some(status) = &app.input_status {
+        frame.render_widget(
+            Paragraph::new(status.as_str()).wrap(Wrap { trim: true }).style(Style::default().fg(Color::Yellow)),
+            input_chunks[2],
+        );
+    }
 }
 
 /// Helper to create a centered rectangle with given width and height percentages.
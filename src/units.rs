@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+
+/// Temperature units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+/// Wind speed units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+    Beaufort,
+}
+
+/// Atmospheric pressure units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PressureUnit {
+    Hectopascal,
+    InchesOfMercury,
+    MillimetersOfMercury,
+}
+
+/// Distance units (visibility).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DistanceUnit {
+    Kilometers,
+    Miles,
+}
+
+/// Precipitation amount units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrecipitationUnit {
+    Millimeters,
+    Inches,
+}
+
+/// Base unit system the per-quantity overrides are applied on top of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    /// Both systems, in the order they are offered.
+    pub const ALL: [UnitSystem; 2] = [UnitSystem::Metric, UnitSystem::Imperial];
+}
+
+/// Unit settings, read from the `[units]` config section.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnitSettings {
+    /// Base system
+    pub system: UnitSystem,
+    /// Overrides for single quantities, e.g. `wind = "knots"`
+    pub temperature: Option<TemperatureUnit>,
+    pub wind: Option<SpeedUnit>,
+    pub pressure: Option<PressureUnit>,
+    pub distance: Option<DistanceUnit>,
+    pub precipitation: Option<PrecipitationUnit>,
+}
+
+impl UnitSettings {
+    /// The units to display, i.e. the system defaults with the overrides applied.
+    pub fn resolve(&self) -> Units {
+        let base = match self.system {
+            UnitSystem::Metric => Units::METRIC,
+            UnitSystem::Imperial => Units::IMPERIAL,
+        };
+        Units {
+            temperature: self.temperature.unwrap_or(base.temperature),
+            wind: self.wind.unwrap_or(base.wind),
+            pressure: self.pressure.unwrap_or(base.pressure),
+            distance: self.distance.unwrap_or(base.distance),
+            precipitation: self.precipitation.unwrap_or(base.precipitation),
+        }
+    }
+
+    /// Switches between the metric and imperial base systems.
+    pub fn toggle_system(&mut self) {
+        self.system = match self.system {
+            UnitSystem::Metric => UnitSystem::Imperial,
+            UnitSystem::Imperial => UnitSystem::Metric,
+        };
+    }
+}
+
+/// The units values are displayed in.
+///
+/// All data is kept in metric (°C, m/s, hPa, m, mm); this is the only place converting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub wind: SpeedUnit,
+    pub pressure: PressureUnit,
+    pub distance: DistanceUnit,
+    pub precipitation: PrecipitationUnit,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self::METRIC
+    }
+}
+
+impl Units {
+    pub const METRIC: Units = Units {
+        temperature: TemperatureUnit::Celsius,
+        wind: SpeedUnit::MetersPerSecond,
+        pressure: PressureUnit::Hectopascal,
+        distance: DistanceUnit::Kilometers,
+        precipitation: PrecipitationUnit::Millimeters,
+    };
+
+    pub const IMPERIAL: Units = Units {
+        temperature: TemperatureUnit::Fahrenheit,
+        wind: SpeedUnit::MilesPerHour,
+        pressure: PressureUnit::InchesOfMercury,
+        distance: DistanceUnit::Miles,
+        precipitation: PrecipitationUnit::Inches,
+    };
+
+    /// Converts a temperature from °C.
+    pub fn temperature_value(&self, celsius: f64) -> f64 {
+        match self.temperature {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            TemperatureUnit::Kelvin => celsius + 273.15,
+        }
+    }
+
+    /// Symbol of the temperature unit.
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self.temperature {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+            TemperatureUnit::Kelvin => "K",
+        }
+    }
+
+    /// Formats a temperature given in °C, e.g. "21.5°F".
+    pub fn temperature(&self, celsius: f64) -> String {
+        format!("{:.1}{}", self.temperature_value(celsius), self.temperature_symbol())
+    }
+
+    /// Formats a wind speed given in m/s, e.g. "12.4 kn" or "Bft 4".
+    pub fn speed(&self, meters_per_second: f64) -> String {
+        match self.wind {
+            SpeedUnit::MetersPerSecond => format!("{:.1} m/s", meters_per_second),
+            SpeedUnit::KilometersPerHour => format!("{:.1} km/h", meters_per_second * 3.6),
+            SpeedUnit::MilesPerHour => format!("{:.1} mph", meters_per_second * 2.236_936),
+            SpeedUnit::Knots => format!("{:.1} kn", meters_per_second * 1.943_844),
+            SpeedUnit::Beaufort => format!("Bft {}", beaufort(meters_per_second)),
+        }
+    }
+
+    /// Formats a pressure given in hPa.
+    pub fn pressure(&self, hectopascal: f64) -> String {
+        match self.pressure {
+            PressureUnit::Hectopascal => format!("{:.0} hPa", hectopascal),
+            PressureUnit::InchesOfMercury => format!("{:.2} inHg", hectopascal * 0.029_529_98),
+            PressureUnit::MillimetersOfMercury => format!("{:.0} mmHg", hectopascal * 0.750_061_6),
+        }
+    }
+
+    /// Formats a distance given in meters.
+    pub fn distance(&self, meters: f64) -> String {
+        match self.distance {
+            DistanceUnit::Kilometers => format!("{:.1} km", meters / 1000.0),
+            DistanceUnit::Miles => format!("{:.1} mi", meters / 1609.344),
+        }
+    }
+
+    /// Formats a precipitation amount given in mm.
+    pub fn precipitation(&self, millimeters: f64) -> String {
+        match self.precipitation {
+            PrecipitationUnit::Millimeters => format!("{:.1} mm", millimeters),
+            PrecipitationUnit::Inches => format!("{:.2} in", millimeters / 25.4),
+        }
+    }
+}
+
+/// Beaufort force for a wind speed in m/s.
+fn beaufort(meters_per_second: f64) -> u8 {
+    const UPPER_LIMITS: [f64; 12] = [0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7];
+    UPPER_LIMITS.iter().position(|&limit| meters_per_second < limit).unwrap_or(12) as u8
+}
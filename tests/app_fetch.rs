@@ -0,0 +1,75 @@
+mod common;
+
+use common::{app_with, named, MockServer, UNKNOWN_CITY};
+use ratatui_templates::app::{App, WeatherState};
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::{Location, WeatherError};
+use ratatui_templates::event::Event;
+use tokio::sync::mpsc;
+
+/// Runs the pending fetches and applies their results the way the main loop does.
+async fn run_fetches(app: &mut App) {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let expected = app.pending_fetches.len();
+    app.fetch_weather(&sender);
+    for _ in 0..expected {
+        match receiver.recv().await.expect("fetch result") {
+            Event::WeatherLoaded(key, data) => app.weather_loaded(key, *data),
+            Event::WeatherFailed(key, error) => app.weather_failed(key, error),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn load_weather_fills_every_city() {
+    let server = MockServer::start().await;
+    let mut app = app_with(Config {
+        endpoints: server.endpoints(),
+        cities: named(&["London", UNKNOWN_CITY]),
+        ..Config::default()
+    });
+
+    app.load_weather();
+    assert!(app.fetch_requested);
+    run_fetches(&mut app).await;
+    assert!(!app.fetch_requested);
+
+    let london = Location::named("London");
+    assert!(matches!(app.weather_state(&london), WeatherState::Loaded(_)));
+    let weather = app.weather_for(&london).expect("London weather");
+    assert_eq!(weather.weather.name, "London");
+    assert_eq!(weather.weather.hourly_temps.as_ref().map(Vec::len), Some(8));
+    assert_eq!(weather.forecast.as_ref().map(|f| f.entries.len()), Some(10));
+    let air_quality = weather.air_quality.as_ref().expect("air quality");
+    assert_eq!(air_quality.current.aqi, 2);
+    assert_eq!(air_quality.forecast.len(), 4);
+    assert_eq!(weather.alerts.len(), 1);
+    assert_eq!(weather.alerts[0].sender, "Met Office");
+    let nowcast = weather.nowcast.as_ref().expect("nowcast");
+    assert_eq!(nowcast.uv_index, Some(3.2));
+    assert_eq!(nowcast.minutely.len(), 61);
+    assert!(weather.stale_since.is_none());
+
+    let nowhere = Location::named(UNKNOWN_CITY);
+    assert_eq!(
+        app.weather_state(&nowhere),
+        &WeatherState::Failed(WeatherError::CityNotFound(UNKNOWN_CITY.to_string())),
+    );
+    assert!(app.weather_for(&nowhere).is_none());
+}
+
+#[tokio::test]
+async fn refresh_selected_fetches_again() {
+    let server = MockServer::start().await;
+    let mut app = app_with(Config { endpoints: server.endpoints(), cities: named(&["London"]), ..Config::default() });
+
+    app.load_weather();
+    run_fetches(&mut app).await;
+    assert_eq!(server.hits("/data/2.5/weather"), 1);
+
+    app.refresh_selected();
+    run_fetches(&mut app).await;
+    assert_eq!(server.hits("/data/2.5/weather"), 2);
+    assert!(matches!(app.weather_state(&Location::named("London")), WeatherState::Loaded(_)));
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use std::fs;
+use common::{app_with, named, scratch_dir};
+use ratatui::layout::Rect;
+use ratatui_templates::cities::{self, CityGroup, CityRow};
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::Location;
+
+fn names(cities: &[Location]) -> Vec<&str> {
+    cities.iter().map(|city| city.name.as_str()).collect()
+}
+
+fn in_group(name: &str, group: &str) -> Location {
+    Location { group: Some(group.to_string()), ..Location::named(name) }
+}
+
+#[test]
+fn arrange_puts_pinned_first_and_groups_last() {
+    let mut cities = vec![
+        in_group("Lyon", "Offices"),
+        Location::named("Paris"),
+        in_group("Iasi", "Family"),
+        Location { pinned: true, ..Location::named("Tokyo") },
+        in_group("Berlin", "Offices"),
+    ];
+    let mut groups = vec![CityGroup::named("Family"), CityGroup::named("Empty")];
+    let selected = cities::arrange(&mut cities, &mut groups, 4);
+
+    assert_eq!(names(&cities), ["Tokyo", "Paris", "Iasi", "Lyon", "Berlin"]);
+    assert_eq!(selected, 4);
+    assert_eq!(groups, [CityGroup::named("Family"), CityGroup::named("Offices")]);
+
+    groups[0].collapsed = true;
+    assert_eq!(
+        cities::rows(&cities, &groups),
+        [CityRow::City(0), CityRow::City(1), CityRow::Group(0), CityRow::Group(1), CityRow::City(3), CityRow::City(4)],
+    );
+}
+
+#[test]
+fn moving_past_a_header_changes_the_group() {
+    let mut app = app_with(Config { cities: vec![Location::named("Paris"), in_group("Lyon", "Offices")], ..Config::default() });
+    // Paris, ▾ Offices, Lyon
+    app.move_selected(1);
+    assert_eq!(names(&app.cities), ["Paris", "Lyon"]);
+    assert_eq!(app.cities[0].group, Some("Offices".to_string()));
+    assert_eq!(app.selected_city, 0);
+    assert!(app.config_changed);
+
+    app.move_selected(1);
+    assert_eq!(names(&app.cities), ["Lyon", "Paris"]);
+    assert_eq!(app.selected_city, 1);
+
+    // Back above the header, where it leaves the group
+    app.move_selected(-1);
+    app.move_selected(-1);
+    assert_eq!(app.cities[0].name, "Paris");
+    assert_eq!(app.cities[0].group, None);
+
+    // Nothing above the first line
+    app.move_selected(-1);
+    assert_eq!(names(&app.cities), ["Paris", "Lyon"]);
+}
+
+#[test]
+fn pin_group_and_collapse() {
+    let mut app = app_with(Config { cities: named(&["Paris", "Tokyo", "Lima"]), ..Config::default() });
+    app.next_city();
+    app.toggle_pin();
+    assert_eq!(names(&app.cities), ["Tokyo", "Paris", "Lima"]);
+    assert_eq!(app.selected_city, 0);
+
+    app.next_city();
+    app.open_group_input();
+    "Family".chars().for_each(|c| app.handle_input(c));
+    app.apply_group_input();
+    assert_eq!(names(&app.cities), ["Tokyo", "Lima", "Paris"]);
+    assert_eq!(app.groups, [CityGroup::named("Family")]);
+
+    // Collapsing hides Paris and moves the selection to its header
+    app.toggle_group();
+    assert_eq!(app.selected_group, Some(0));
+    assert_eq!(app.city_rows(), [CityRow::City(0), CityRow::City(1), CityRow::Group(0)]);
+    app.remove_selected_city();
+    assert_eq!(app.cities.len(), 3, "a selected header is not deleted");
+
+    // Renaming the header to nothing dissolves the group
+    app.open_group_input();
+    assert_eq!(app.input, "Family");
+    app.input.clear();
+    app.apply_group_input();
+    assert!(app.groups.is_empty());
+    assert_eq!(app.selected_group, None);
+    assert_eq!(app.cities[2].group, None);
+}
+
+#[test]
+fn dragging_follows_the_mouse_and_clicking_a_header_folds_it() {
+    let mut app = app_with(Config {
+        cities: vec![Location::named("Paris"), Location::named("Tokyo"), in_group("Lima", "Trips")],
+        ..Config::default()
+    });
+    // As if the list was drawn at the top left without scrolling
+    app.city_list_area = Some((Rect::new(1, 1, 40, 6), 0));
+
+    app.click_city_list(5, 1);
+    assert!(app.drag_origin.is_some());
+    app.drag_city(2);
+    assert_eq!(names(&app.cities), ["Tokyo", "Paris", "Lima"]);
+    // Past the end of the list it goes to the last line, inside the group
+    app.drag_city(30);
+    app.end_drag();
+    assert_eq!(names(&app.cities), ["Tokyo", "Lima", "Paris"]);
+    assert_eq!(app.cities[2].group, Some("Trips".to_string()));
+
+    // Lines: Tokyo, ▾ Trips, Lima, Paris
+    app.click_city_list(5, 2);
+    assert!(app.groups[0].collapsed);
+    assert!(app.drag_origin.is_none());
+    app.drag_city(1);
+    assert_eq!(names(&app.cities), ["Tokyo", "Lima", "Paris"]);
+
+    // A list squeezed to no lines cannot be aimed at
+    app.click_city_list(5, 1);
+    app.city_list_area = Some((Rect::new(1, 1, 40, 0), 0));
+    app.drag_city(3);
+    app.end_drag();
+    assert_eq!(names(&app.cities), ["Tokyo", "Lima", "Paris"]);
+}
+
+#[test]
+fn a_city_hidden_in_a_collapsed_group_is_never_selected() {
+    let cities = vec![Location::named("Paris"), in_group("Lyon", "Offices"), in_group("Nice", "Offices")];
+    let groups = vec![CityGroup { name: "Offices".to_string(), collapsed: true }];
+    let started = app_with(Config { cities: cities.clone(), groups: groups.clone(), selected_city: 1, ..Config::default() });
+    assert_eq!(started.selected_group, Some(0));
+
+    // Deleting Paris would otherwise leave Lyon selected behind the header
+    let mut app = app_with(Config { cities, groups, ..Config::default() });
+    app.remove_selected_city();
+    assert_eq!(app.selected_group, Some(0));
+    app.remove_selected_city();
+    assert_eq!(names(&app.cities), ["Lyon", "Nice"], "a selected header is not deleted");
+
+    // A city added while the header is selected joins the group and stays hidden
+    app.enter_edit_mode();
+    app.input = "50.63, 3.06".to_string();
+    app.search_city();
+    assert_eq!(app.cities.len(), 3);
+    assert_eq!(app.cities[app.selected_city].group.as_deref(), Some("Offices"));
+    assert_eq!(app.selected_group, Some(0));
+}
+
+#[test]
+fn groups_and_pins_survive_a_restart() {
+    let dir = scratch_dir("cities");
+    let path = dir.join("config.toml");
+    let mut app = app_with(Config { cities: vec![Location::named("Paris"), in_group("Lyon", "Offices")], ..Config::default() });
+    app.config_path = Some(path.clone());
+    app.next_city();
+    app.toggle_group();
+    app.previous_city();
+    app.toggle_pin();
+    app.save_config();
+    assert_eq!(app.config_error, None);
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.groups, [CityGroup { name: "Offices".to_string(), collapsed: true }]);
+    let restarted = app_with(config);
+    assert_eq!(restarted.cities, app.cities);
+    assert!(restarted.cities[0].pinned);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,168 @@
+// Each test crate uses its own subset of these helpers
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use ratatui_templates::app::App;
+use ratatui_templates::cache::CacheSettings;
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::{Endpoints, Location};
+use ratatui_templates::icons::IconSettings;
+use reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// API key the mock server rejects with 401.
+pub const BAD_KEY: &str = "bad";
+/// City the mock server does not know (404).
+pub const UNKNOWN_CITY: &str = "Nowhere";
+/// City the mock server always answers with 429.
+pub const RATE_LIMITED_CITY: &str = "Busytown";
+/// City the mock server answers with a truncated JSON body.
+pub const MALFORMED_CITY: &str = "Garbled";
+
+/// A fresh, empty directory under the system temp dir, unique to `test` and this run.
+pub fn scratch_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("weather-cli-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+/// An app built from `config`, without disk cache or icons so tests never touch the user's cache.
+pub fn app_with(config: Config) -> App {
+    let config = Config {
+        cache: CacheSettings { enabled: false, ..CacheSettings::default() },
+        icons: IconSettings { enabled: false, ..IconSettings::default() },
+        ..config
+    };
+    App::from_config(&config, "test-key".to_string())
+}
+
+/// Cities looked up by name.
+pub fn named(names: &[&str]) -> Vec<Location> {
+    names.iter().copied().map(Location::named).collect()
+}
+
+/// A canned HTTP response.
+struct Reply {
+    status: u16,
+    headers: Vec<(&'static str, &'static str)>,
+    body: &'static str,
+}
+
+impl Reply {
+    fn json(status: u16, body: &'static str) -> Self {
+        Self { status, headers: Vec::new(), body }
+    }
+}
+
+/// Local stand-in for the OpenWeather API, serving recorded responses from `tests/fixtures/openweather`.
+///
+/// Runs until the test's runtime shuts down.
+pub struct MockServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Binds to a free local port and starts answering requests.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().expect("mock server address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, log.clone()));
+            }
+        });
+        Self { base_url, requests }
+    }
+
+    /// Endpoints sending every service to this server.
+    pub fn endpoints(&self) -> Endpoints {
+        Endpoints {
+            openweather: self.base_url.clone(),
+            open_meteo: self.base_url.clone(),
+            open_meteo_archive: self.base_url.clone(),
+            open_meteo_geocoding: self.base_url.clone(),
+            met_no: self.base_url.clone(),
+        }
+    }
+
+    /// Request targets (path and query) received so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// How many requests went to `path`.
+    pub fn hits(&self, path: &str) -> usize {
+        self.requests().iter().filter(|target| target.split('?').next() == Some(path)).count()
+    }
+}
+
+/// Answers a single request and closes the connection.
+async fn serve(mut stream: TcpStream, log: Arc<Mutex<Vec<String>>>) {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+    }
+    let head = String::from_utf8_lossy(&head);
+    let target = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+    log.lock().unwrap().push(target.clone());
+
+    let reply = route(&target);
+    let mut response = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        reply.status,
+        reply.body.len(),
+    );
+    for (name, value) in reply.headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(reply.body);
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Picks the response for a request target, mimicking what OpenWeather sends.
+fn route(target: &str) -> Reply {
+    let url = Url::parse(&format!("http://mock{}", target)).expect("request target");
+    let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+    let param = |name: &str| query.get(name).map(String::as_str);
+
+    if param("appid") == Some(BAD_KEY) {
+        return Reply::json(401, r#"{"cod":401,"message":"Invalid API key. Please see https://openweathermap.org/faq#error401 for more info."}"#);
+    }
+    match param("q") {
+        Some(UNKNOWN_CITY) => return Reply::json(404, r#"{"cod":"404","message":"city not found"}"#),
+        Some(RATE_LIMITED_CITY) => {
+            return Reply {
+                status: 429,
+                headers: vec![("Retry-After", "0")],
+                body: r#"{"cod":429,"message":"Your account is temporary blocked due to exceeding of requests limitation of your subscription type."}"#,
+            };
+        }
+        Some(MALFORMED_CITY) => return Reply::json(200, include_str!("../fixtures/openweather/malformed.json")),
+        _ => {}
+    }
+    match url.path() {
+        "/data/2.5/weather" => Reply::json(200, include_str!("../fixtures/openweather/current.json")),
+        "/data/2.5/forecast" => Reply::json(200, include_str!("../fixtures/openweather/forecast.json")),
+        "/data/2.5/air_pollution" => Reply::json(200, include_str!("../fixtures/openweather/air_pollution.json")),
+        "/data/2.5/air_pollution/forecast" => {
+            Reply::json(200, include_str!("../fixtures/openweather/air_pollution_forecast.json"))
+        }
+        "/geo/1.0/direct" => Reply::json(200, include_str!("../fixtures/openweather/geocoding.json")),
+        "/data/3.0/onecall" => Reply::json(200, include_str!("../fixtures/openweather/onecall.json")),
+        _ => Reply::json(404, r#"{"cod":"404","message":"Internal error"}"#),
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::fs;
+use common::{app_with, scratch_dir};
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::{Location, LocationQuery};
+use ratatui_templates::units::UnitSystem;
+
+#[test]
+fn missing_file_yields_default_cities() {
+    let config = Config::load_from(&scratch_dir("missing").join("config.toml")).unwrap();
+    assert_eq!(config.cities.len(), 10);
+    assert_eq!(config.cities[0], Location::named("Bucharest"));
+    assert_eq!(config.selected_city, 0);
+}
+
+#[test]
+fn app_changes_survive_a_restart() {
+    // Saving creates the directories that are missing
+    let path = scratch_dir("restart").join("nested").join("config.toml");
+    let mut app = app_with(Config::default());
+    app.config_path = Some(path.clone());
+
+    app.cities = vec![
+        Location::named("Tokyo"),
+        Location::from_query(LocationQuery::Coordinates { lat: 44.43, lon: 26.1 }),
+    ];
+    app.next_city();
+    assert!(!app.config_changed, "browsing the list does not rewrite the config");
+    assert!(app.selection_changed);
+    app.toggle_units();
+    assert!(app.config_changed);
+    app.save_config();
+    assert!(!app.config_changed && !app.selection_changed);
+    assert_eq!(app.config_error, None);
+    assert!(!path.with_extension("tmp").exists());
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.cities, app.cities);
+    assert_eq!(config.selected_city, 1);
+    assert_eq!(config.units.system, UnitSystem::Imperial);
+
+    let restarted = app_with(config);
+    assert_eq!(restarted.cities, app.cities);
+    assert_eq!(restarted.selected_city, 1);
+    let _ = fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[test]
+fn selection_is_clamped_to_the_list() {
+    let config = Config { selected_city: 42, cities: vec![Location::named("Paris")], ..Config::default() };
+    assert_eq!(app_with(config).selected_city, 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn saving_keeps_a_private_config_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let path = scratch_dir("private").join("config.toml");
+    Config::default().save_to(&path).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+    Config::default().save_to(&path).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{MockServer, BAD_KEY, MALFORMED_CITY, RATE_LIMITED_CITY, UNKNOWN_CITY};
+use ratatui_templates::connection::openweather::parse_current_weather;
+use ratatui_templates::connection::{
+    get_data_from, Endpoints, HttpClient, NetworkStatus, OpenWeather, WeatherError, WeatherProvider,
+};
+use ratatui_templates::i18n::Language;
+
+const KEY: &str = "test-key";
+
+#[tokio::test]
+async fn parses_current_weather() {
+    let server = MockServer::start().await;
+    let city = get_data_from(&HttpClient::default(), &server.endpoints(), "London".to_string(), KEY).await.unwrap();
+
+    assert_eq!(city.name, "London");
+    assert_eq!(city.country, "GB");
+    assert_eq!(city.temperature, 12.4);
+    assert_eq!(city.humidity, 82);
+    assert_eq!(city.description, "light rain");
+    assert_eq!(city.icon, "10d");
+    assert_eq!(city.wind_direction, Some(230));
+    assert_eq!(city.wind_gust, Some(9.3));
+    assert_eq!(city.rain_1h, Some(0.42));
+    assert_eq!(city.utc_offset, Some(3600));
+    assert_eq!(city.timezone_label(), "UTC+01:00");
+    assert!(city.sunrise.is_some() && city.sunset.is_some());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("/data/2.5/weather?q=London&appid=test-key&units=metric"));
+}
+
+#[tokio::test]
+async fn rejected_key_is_reported() {
+    let server = MockServer::start().await;
+    let result = get_data_from(&HttpClient::default(), &server.endpoints(), "London".to_string(), BAD_KEY).await;
+
+    assert_eq!(result.unwrap_err(), WeatherError::InvalidApiKey);
+    assert_eq!(server.hits("/data/2.5/weather"), 1);
+}
+
+#[tokio::test]
+async fn unknown_city_is_reported() {
+    let server = MockServer::start().await;
+    let result = get_data_from(&HttpClient::default(), &server.endpoints(), UNKNOWN_CITY.to_string(), KEY).await;
+
+    let error = result.unwrap_err();
+    assert_eq!(error, WeatherError::CityNotFound(UNKNOWN_CITY.to_string()));
+    assert_eq!(server.hits("/data/2.5/weather"), 1);
+
+    // Shown in the UI language
+    let messages = Language::German.messages();
+    assert_eq!(error.message(messages), "Stadt „Nowhere“ wurde nicht gefunden");
+    assert!(error.suggestion(messages).contains("Ländercode"));
+    assert_eq!(error.to_string(), "City \"Nowhere\" was not found");
+}
+
+#[tokio::test]
+async fn missing_endpoint_is_not_an_unknown_city() {
+    let server = MockServer::start().await;
+    // The mock server answers 404 to every path it does not serve
+    let endpoints = Endpoints { openweather: format!("{}/missing", server.endpoints().openweather), ..server.endpoints() };
+    let provider = OpenWeather::new(KEY, HttpClient::default()).with_endpoints(endpoints);
+
+    let error = provider.alerts(51.51, -0.13).await.unwrap_err();
+    assert!(matches!(error, WeatherError::Api { status: 404, .. }), "{:?}", error);
+}
+
+#[tokio::test]
+async fn rate_limit_is_retried_then_reported() {
+    let server = MockServer::start().await;
+    let result = get_data_from(&HttpClient::default(), &server.endpoints(), RATE_LIMITED_CITY.to_string(), KEY).await;
+
+    assert_eq!(result.unwrap_err(), WeatherError::RateLimited);
+    // The first attempt plus the default three retries, each after the server's `Retry-After: 0`
+    assert_eq!(server.hits("/data/2.5/weather"), 4);
+}
+
+#[tokio::test]
+async fn giving_up_is_reported_until_that_host_answers_again() {
+    let server = MockServer::start().await;
+    let http = HttpClient::default();
+    let endpoints = server.endpoints();
+    // Same server under another host name
+    let elsewhere = Endpoints { openweather: endpoints.openweather.replace("127.0.0.1", "localhost"), ..endpoints.clone() };
+
+    let _ = get_data_from(&http, &endpoints, RATE_LIMITED_CITY.to_string(), KEY).await;
+    assert!(matches!(http.status(), Some(NetworkStatus::GaveUp { ref host, retries: 3, .. }) if host == "127.0.0.1"));
+
+    get_data_from(&http, &elsewhere, "London".to_string(), KEY).await.unwrap();
+    assert!(matches!(http.status(), Some(NetworkStatus::GaveUp { .. })), "{:?}", http.status());
+
+    get_data_from(&http, &endpoints, "London".to_string(), KEY).await.unwrap();
+    assert_eq!(http.status(), None);
+}
+
+#[test]
+fn wind_without_a_direction_still_parses() {
+    let body = include_str!("fixtures/openweather/current.json").replace(r#""deg": 230, "#, "");
+    let city = parse_current_weather(&body).unwrap();
+
+    assert_eq!(city.wind_speed, 5.1);
+    assert_eq!(city.wind_direction, None);
+}
+
+#[tokio::test]
+async fn malformed_body_is_a_parse_error() {
+    let server = MockServer::start().await;
+    let result = get_data_from(&HttpClient::default(), &server.endpoints(), MALFORMED_CITY.to_string(), KEY).await;
+
+    assert!(matches!(result, Err(WeatherError::Parse(_))), "{:?}", result);
+    assert_eq!(server.hits("/data/2.5/weather"), 1);
+}
@@ -0,0 +1,31 @@
+use ratatui_templates::connection::met_no::parse_current;
+use ratatui_templates::connection::GeoLocation;
+
+/// A single-step location forecast taken at midnight UTC on a summer day.
+const FORECAST: &str = r#"{"properties":{"timeseries":[{"time":"2026-07-01T00:00:00Z","data":{
+    "instant":{"details":{"air_temperature":18.2,"air_pressure_at_sea_level":1012.4,"relative_humidity":71.0,
+        "wind_speed":3.1,"wind_from_direction":240.0,"cloud_area_fraction":12.5}},
+    "next_1_hours":{"summary":{"symbol_code":"clearsky_night"},"details":{"precipitation_amount":0.0}}}}]}}"#;
+
+fn place(timezone: Option<&str>) -> GeoLocation {
+    GeoLocation {
+        name: "Bucharest".to_string(),
+        state: None,
+        country: "RO".to_string(),
+        lat: 44.43,
+        lon: 26.1,
+        timezone: timezone.map(str::to_string),
+    }
+}
+
+#[test]
+fn times_follow_the_geocoded_time_zone() {
+    let weather = parse_current(FORECAST, &place(Some("Europe/Bucharest"))).unwrap();
+    // Summer time on that day
+    assert_eq!(weather.utc_offset, Some(3 * 3600));
+    assert_eq!(weather.timezone_label(), "UTC+03:00");
+
+    let unknown = parse_current(FORECAST, &place(Some("Mars/Olympus"))).unwrap();
+    assert_eq!(unknown.utc_offset, None);
+    assert_eq!(parse_current(FORECAST, &place(None)).unwrap().timezone_label(), "UTC");
+}
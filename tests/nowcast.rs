@@ -0,0 +1,94 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ratatui_templates::connection::{uv_category, MinutePrecipitation, Nowcast, RainOutlook};
+
+fn noon() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 10, 16, 12, 0, 0).unwrap()
+}
+
+/// A nowcast with one entry per minute from `start`, at the given rates in mm/h.
+fn minutely(start: DateTime<Utc>, rates: &[f64]) -> Nowcast {
+    let minutely = rates.iter().enumerate()
+        .map(|(minute, &intensity)| MinutePrecipitation { time: start + Duration::minutes(minute as i64), intensity })
+        .collect();
+    Nowcast { uv_index: None, minutely }
+}
+
+/// An hour of rates: `first` mm/h for `minutes` minutes, then `then` mm/h.
+fn rates(first: f64, minutes: usize, then: f64) -> Vec<f64> {
+    (0..60).map(|minute| if minute < minutes { first } else { then }).collect()
+}
+
+#[test]
+fn next_hour_lines_minutes_up_with_now() {
+    let nowcast = minutely(noon() - Duration::minutes(5), &(0..70).map(f64::from).collect::<Vec<_>>());
+    // Half a minute in, the entry for the current minute still counts
+    let minutes = nowcast.next_hour(noon() + Duration::seconds(30));
+
+    assert_eq!(minutes[0], Some(5.0));
+    assert_eq!(minutes[59], Some(64.0));
+}
+
+#[test]
+fn next_hour_leaves_gaps_and_the_end_empty() {
+    let mut nowcast = minutely(noon(), &[1.0; 20]);
+    nowcast.minutely.remove(10);
+    let minutes = nowcast.next_hour(noon());
+
+    assert_eq!(minutes[9], Some(1.0));
+    assert_eq!(minutes[10], None);
+    assert_eq!(minutes[11], Some(1.0));
+    assert!(minutes[20..].iter().all(Option::is_none));
+}
+
+#[test]
+fn rain_outlook_for_a_whole_hour() {
+    assert_eq!(minutely(noon(), &[0.0; 60]).rain_outlook(noon()), Some(RainOutlook::Dry));
+    assert_eq!(minutely(noon(), &[2.5; 60]).rain_outlook(noon()), Some(RainOutlook::Ongoing));
+    // Drizzle below the threshold is not rain
+    assert_eq!(minutely(noon(), &[0.05; 60]).rain_outlook(noon()), Some(RainOutlook::Dry));
+}
+
+#[test]
+fn rain_outlook_counts_minutes_to_the_change() {
+    let starting = minutely(noon(), &rates(0.0, 25, Nowcast::RAIN_THRESHOLD));
+    assert_eq!(starting.rain_outlook(noon()), Some(RainOutlook::StartsIn(25)));
+
+    let stopping = minutely(noon(), &rates(3.0, 1, 0.0));
+    assert_eq!(stopping.rain_outlook(noon()), Some(RainOutlook::StopsIn(1)));
+    // Ten minutes later the rain stopped long ago
+    assert_eq!(stopping.rain_outlook(noon() + Duration::minutes(10)), Some(RainOutlook::Dry));
+}
+
+#[test]
+fn rain_outlook_only_looks_up_to_the_first_gap() {
+    let mut nowcast = minutely(noon(), &rates(0.0, 30, 1.0));
+    nowcast.minutely.remove(20);
+    assert_eq!(nowcast.rain_outlook(noon()), Some(RainOutlook::Dry));
+
+    // Nothing known about the current minute
+    nowcast.minutely.remove(0);
+    assert_eq!(nowcast.rain_outlook(noon()), None);
+}
+
+#[test]
+fn rain_outlook_needs_minutely_data() {
+    assert_eq!(minutely(noon(), &[]).rain_outlook(noon()), None);
+    assert_eq!(minutely(noon(), &[1.0; 60]).rain_outlook(noon() + Duration::hours(2)), None);
+    assert_eq!(minutely(noon(), &[1.0; 60]).rain_outlook(noon() - Duration::hours(2)), None);
+    // A few minutes are enough
+    assert_eq!(minutely(noon(), &[0.0, 0.0, 1.0]).rain_outlook(noon()), Some(RainOutlook::StartsIn(2)));
+}
+
+#[test]
+fn uv_categories_follow_the_who_scale() {
+    let cases = [
+        (0.0, 0), (2.0, 0), (2.4, 0),
+        (2.5, 1), (3.0, 1), (5.0, 1), (5.4, 1),
+        (5.5, 2), (6.0, 2), (7.0, 2), (7.4, 2),
+        (7.5, 3), (8.0, 3), (10.0, 3), (10.4, 3),
+        (10.5, 4), (11.0, 4), (14.0, 4),
+    ];
+    for (uv_index, category) in cases {
+        assert_eq!(uv_category(uv_index), category, "UV index {}", uv_index);
+    }
+}
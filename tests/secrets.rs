@@ -0,0 +1,98 @@
+mod common;
+
+use std::fs;
+use common::scratch_dir;
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::{get_data_from, Endpoints, HttpClient, WeatherError};
+use ratatui_templates::secrets::{redact_url, resolve_api_key, ApiKeySettings};
+use reqwest::Url;
+
+#[test]
+fn flag_wins_over_config_and_command() {
+    let settings = ApiKeySettings {
+        value: Some("from-config".to_string()),
+        command: Some("echo from-command".to_string()),
+        ..ApiKeySettings::default()
+    };
+    assert_eq!(resolve_api_key(Some("from-flag"), &settings).unwrap().as_deref(), Some("from-flag"));
+    // A blank flag counts as not given
+    assert_eq!(resolve_api_key(Some("  "), &settings).unwrap().as_deref(), Some("from-config"));
+}
+
+#[cfg(unix)]
+#[test]
+fn key_file_must_be_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = scratch_dir("key-file");
+    let file = dir.join("api_key");
+    fs::write(&file, "from-file\n").unwrap();
+    let settings = ApiKeySettings {
+        file: Some(file.clone()),
+        command: Some("echo from-command".to_string()),
+        ..ApiKeySettings::default()
+    };
+
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+    let error = resolve_api_key(None, &settings).unwrap_err().to_string();
+    assert!(error.contains("chmod 600"), "{}", error);
+
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o600)).unwrap();
+    assert_eq!(resolve_api_key(None, &settings).unwrap().as_deref(), Some("from-file"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn config_with_an_inline_key_must_be_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let path = scratch_dir("inline-key").join("config.toml");
+    let config = Config {
+        api_key: ApiKeySettings { value: Some("s3cret".to_string()), ..ApiKeySettings::default() },
+        ..Config::default()
+    };
+    config.save_to(&path).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    assert_eq!(Config::load_from(&path).unwrap().api_key.value.as_deref(), Some("s3cret"));
+
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+    let error = Config::load_from(&path).unwrap_err().to_string();
+    assert!(error.contains("chmod 600"), "{}", error);
+
+    // Without a key in it the config may be shared
+    Config::default().save_to(&path).unwrap();
+    assert!(Config::load_from(&path).is_ok());
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn command_output_is_used_last() {
+    let settings = ApiKeySettings {
+        file: Some(scratch_dir("command").join("missing")),
+        command: Some("printf 'from-command\\nsecond line'".to_string()),
+        ..ApiKeySettings::default()
+    };
+    assert_eq!(resolve_api_key(None, &settings).unwrap().as_deref(), Some("from-command"));
+
+    let failing = ApiKeySettings { command: Some("exit 3".to_string()), ..settings };
+    assert!(resolve_api_key(None, &failing).is_err());
+}
+
+#[test]
+fn redact_url_masks_only_credentials() {
+    let url = Url::parse("https://api.openweathermap.org/data/2.5/weather?q=London&appid=s3cret&units=metric").unwrap();
+    let redacted = redact_url(&url).to_string();
+    assert!(!redacted.contains("s3cret"));
+    assert!(redacted.contains("q=London") && redacted.contains("appid=REDACTED") && redacted.contains("units=metric"));
+}
+
+#[tokio::test]
+async fn network_errors_do_not_leak_the_key() {
+    // Nothing listens on the discard port, so the connection is refused
+    let endpoints = Endpoints { openweather: "http://127.0.0.1:9".to_string(), ..Endpoints::default() };
+    let error = get_data_from(&HttpClient::default(), &endpoints, "London".to_string(), "s3cret-key").await.unwrap_err();
+
+    assert!(matches!(error, WeatherError::NetworkUnreachable(_)), "{:?}", error);
+    assert!(!error.to_string().contains("s3cret-key"), "{}", error);
+    assert!(!format!("{:?}", error).contains("s3cret-key"));
+}
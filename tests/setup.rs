@@ -0,0 +1,160 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use common::{scratch_dir, MockServer, BAD_KEY};
+use ratatui_templates::app::{App, InputMode};
+use ratatui_templates::cache::CacheSettings;
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::{Location, LocationQuery, ProviderKind};
+use ratatui_templates::event::Event;
+use ratatui_templates::i18n::Language;
+use ratatui_templates::icons::IconSettings;
+use ratatui_templates::secrets::resolve_api_key;
+use ratatui_templates::setup::SetupStep;
+use ratatui_templates::units::UnitSystem;
+use tokio::sync::mpsc;
+
+/// An app in the wizard, talking to the mock server and saving to `path`.
+fn app_in_setup(server: &MockServer, path: &Path) -> App {
+    let config = Config {
+        endpoints: server.endpoints(),
+        cache: CacheSettings { enabled: false, ..CacheSettings::default() },
+        icons: IconSettings { enabled: false, ..IconSettings::default() },
+        ..Config::default()
+    };
+    let mut app = App::from_config(&config, String::new());
+    app.config_path = Some(path.to_path_buf());
+    app.start_setup();
+    app
+}
+
+/// Starts a background request with `start` and applies its result the way the main loop does.
+async fn run_in_background(app: &mut App, start: impl FnOnce(&mut App, &mpsc::UnboundedSender<Event>)) {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    start(app, &sender);
+    match receiver.recv().await.expect("background result") {
+        Event::GeocodeLoaded(query, candidates) => app.geocode_loaded(query, candidates),
+        Event::GeocodeFailed(query, error) => app.geocode_failed(query, error),
+        Event::KeyChecked(key, result) => app.key_checked(key, result),
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+fn type_text(app: &mut App, text: &str) {
+    while !app.input.is_empty() {
+        app.delete_char();
+    }
+    text.chars().for_each(|c| app.handle_input(c));
+}
+
+#[tokio::test]
+async fn wizard_checks_the_key_and_writes_the_config() {
+    let server = MockServer::start().await;
+    let path = scratch_dir("setup-openweather").join("config.toml");
+    let mut app = app_in_setup(&server, &path);
+    assert_eq!(app.input_mode, InputMode::Setup);
+    assert_eq!(app.setup.progress(), (1, 5));
+
+    // OpenWeather is offered first and needs a key
+    app.setup_confirm();
+    assert_eq!(app.setup.step, SetupStep::ApiKey);
+    app.setup_confirm();
+    assert!(!app.setup.key_check_requested, "an empty key is not checked");
+
+    type_text(&mut app, BAD_KEY);
+    app.setup_confirm();
+    assert!(app.setup.key_check_requested);
+    run_in_background(&mut app, App::check_setup_key).await;
+    assert_eq!(app.setup.step, SetupStep::ApiKey);
+    assert!(app.input_status.as_deref().is_some_and(|status| status.contains("Invalid API key")));
+
+    type_text(&mut app, "good-key");
+    app.setup_confirm();
+    run_in_background(&mut app, App::check_setup_key).await;
+    assert_eq!(app.setup.step, SetupStep::Units);
+    assert_eq!(app.api_key, "good-key");
+
+    app.setup_cycle(1);
+    app.setup_confirm();
+    app.setup_cycle(-1);
+    assert_eq!(app.messages().setup_step, "Schritt {} von {}", "the wizard switches language right away");
+    app.setup_confirm();
+    assert_eq!(app.setup.step, SetupStep::HomeCity);
+
+    type_text(&mut app, "Bucharest");
+    app.setup_confirm();
+    assert!(app.geocode_requested);
+    run_in_background(&mut app, App::geocode_input).await;
+    assert_eq!(app.input_mode, InputMode::Setup);
+    assert_eq!(app.candidates.len(), 2);
+    app.setup_cycle(1);
+    app.setup_cycle(1);
+    app.setup_confirm();
+
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert_eq!(app.config_error, None);
+    assert_eq!(app.cities.len(), 1);
+    assert_eq!(app.cities[0].name, "Bucharest");
+    assert_eq!(app.pending_fetches.len(), 1);
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.provider, ProviderKind::OpenWeather);
+    assert_eq!(config.units.system, UnitSystem::Imperial);
+    assert_eq!(config.locale.language, Language::German);
+    assert_eq!(config.cities, app.cities);
+    assert_eq!(config.api_key.value, None, "the key is not stored in the config itself");
+    assert_eq!(config.api_key.file, Some(path.with_file_name("api_key")));
+    assert_eq!(resolve_api_key(None, &config.api_key).unwrap().as_deref(), Some("good-key"));
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn leaving_the_key_page_drops_a_pending_check() {
+    let server = MockServer::start().await;
+    let mut app = app_in_setup(&server, &scratch_dir("setup-cancel").join("config.toml"));
+    app.setup_confirm();
+    type_text(&mut app, "good-key");
+    app.setup_confirm();
+
+    // The wizard keeps handling keys while the check runs
+    run_in_background(&mut app, |app, sender| {
+        app.check_setup_key(sender);
+        app.setup_back();
+    }).await;
+    assert_eq!(app.setup.step, SetupStep::Provider);
+    assert_eq!(app.api_key, "");
+}
+
+#[tokio::test]
+async fn services_without_a_key_skip_the_key_page() {
+    let server = MockServer::start().await;
+    let path = scratch_dir("setup-open-meteo").join("config.toml");
+    let mut app = app_in_setup(&server, &path);
+
+    app.setup_cycle(1);
+    assert_eq!(app.setup.progress(), (1, 4));
+    app.setup_confirm();
+    assert_eq!(app.setup.step, SetupStep::Units);
+
+    // Going back returns to the service choice, then leaves the app
+    app.setup_back();
+    assert_eq!(app.setup.step, SetupStep::Provider);
+    app.setup_confirm();
+    app.setup_confirm();
+    app.setup_confirm();
+    type_text(&mut app, "44.43, 26.1");
+    app.setup_confirm();
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.provider, ProviderKind::OpenMeteo);
+    assert_eq!(config.api_key.file, None);
+    assert!(!path.with_file_name("api_key").exists());
+    assert_eq!(config.cities, vec![Location::from_query(LocationQuery::Coordinates { lat: 44.43, lon: 26.1 })]);
+    assert_eq!(server.requests(), Vec::<String>::new());
+
+    let mut quitting = app_in_setup(&server, &scratch_dir("setup-quit").join("config.toml"));
+    quitting.setup_back();
+    assert!(!quitting.running);
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
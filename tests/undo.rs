@@ -0,0 +1,154 @@
+mod common;
+
+use std::time::Duration;
+use common::{app_with, named};
+use ratatui::layout::Rect;
+use ratatui_templates::app::{App, WeatherState};
+use ratatui_templates::cities::CityGroup;
+use ratatui_templates::config::Config;
+use ratatui_templates::connection::Location;
+use ratatui_templates::refresh::RefreshSettings;
+
+fn names(app: &App) -> Vec<&str> {
+    app.cities.iter().map(|city| city.name.as_str()).collect()
+}
+
+fn toast(app: &App) -> &str {
+    app.toast.as_ref().map(|(toast, _)| toast.as_str()).unwrap_or_default()
+}
+
+#[test]
+fn removing_can_be_undone_and_redone() {
+    let mut app = app_with(Config { cities: named(&["Paris", "Tokyo", "Lima"]), ..Config::default() });
+    app.next_city();
+    app.remove_selected_city();
+    assert_eq!(names(&app), ["Paris", "Lima"]);
+    assert_eq!(toast(&app), "Removed Tokyo — press u to undo");
+
+    app.undo();
+    assert_eq!(names(&app), ["Paris", "Tokyo", "Lima"]);
+    assert_eq!(app.selected_city, 1);
+    assert_eq!(toast(&app), "Undone: Removed Tokyo");
+    // The city's data was dropped with it, so it is fetched again
+    assert_eq!(*app.weather_state(&Location::named("Tokyo")), WeatherState::Loading);
+    assert!(app.config_changed);
+
+    app.redo();
+    assert_eq!(names(&app), ["Paris", "Lima"]);
+    assert_eq!(toast(&app), "Redone: Removed Tokyo");
+    app.redo();
+    assert_eq!(toast(&app), "Nothing to redo");
+}
+
+#[test]
+fn a_new_edit_clears_the_redo_stack() {
+    let mut app = app_with(Config { cities: named(&["Paris", "Tokyo"]), ..Config::default() });
+    app.remove_selected_city();
+    app.undo();
+    app.toggle_pin();
+    app.redo();
+    assert_eq!(toast(&app), "Nothing to redo");
+    assert_eq!(names(&app), ["Paris", "Tokyo"]);
+
+    // Edits that change nothing are not recorded
+    app.move_selected(-1);
+    app.undo();
+    assert!(!app.cities[0].pinned);
+    app.undo();
+    assert_eq!(toast(&app), "Nothing to undo");
+}
+
+#[test]
+fn a_drag_is_undone_in_one_step() {
+    let mut app = app_with(Config { cities: named(&["Paris", "Tokyo", "Lima"]), ..Config::default() });
+    app.city_list_area = Some((Rect::new(0, 0, 40, 6), 0));
+    app.click_city_list(3, 0);
+    app.drag_city(1);
+    app.drag_city(2);
+    app.end_drag();
+    assert_eq!(names(&app), ["Tokyo", "Lima", "Paris"]);
+    assert_eq!(toast(&app), "Moved Paris — press u to undo");
+
+    app.undo();
+    assert_eq!(names(&app), ["Paris", "Tokyo", "Lima"]);
+    app.undo();
+    assert_eq!(toast(&app), "Nothing to undo");
+}
+
+#[test]
+fn group_changes_are_undone_but_folding_is_kept() {
+    let mut app = app_with(Config { cities: named(&["Paris", "Lyon"]), ..Config::default() });
+    app.next_city();
+    app.open_group_input();
+    "Offices".chars().for_each(|c| app.handle_input(c));
+    app.apply_group_input();
+    assert_eq!(toast(&app), "Moved Lyon to Offices — press u to undo");
+
+    // Rename the group from its header
+    app.previous_city();
+    assert_eq!(app.selected_group, Some(0));
+    app.open_group_input();
+    app.input = "Work".to_string();
+    app.apply_group_input();
+    assert_eq!(app.groups, [CityGroup::named("Work")]);
+
+    app.undo();
+    assert_eq!(toast(&app), "Undone: Renamed group Offices to Work");
+    assert_eq!(app.groups, [CityGroup::named("Offices")]);
+    assert_eq!(app.cities[1].group.as_deref(), Some("Offices"));
+
+    // Folding is not an edit, and survives undoing one
+    app.toggle_group();
+    app.next_city();
+    app.toggle_pin();
+    app.undo();
+    assert!(!app.cities[0].pinned);
+    assert_eq!(app.groups, [CityGroup { name: "Offices".to_string(), collapsed: true }]);
+
+    app.undo();
+    assert!(app.groups.is_empty());
+    assert_eq!(app.cities[1].group, None);
+}
+
+#[test]
+fn renaming_a_city_keeps_its_place_and_can_be_undone() {
+    let mut app = app_with(Config { cities: named(&["Paris", "Tokyo"]), ..Config::default() });
+    let key = app.cities[1].key();
+    app.next_city();
+    app.open_rename_input();
+    assert_eq!(app.input, "Tokyo");
+    app.input = "Home".to_string();
+    app.apply_rename_input();
+    assert_eq!(names(&app), ["Paris", "Home"]);
+    assert_eq!(app.cities[1].key(), key, "the city is still looked up as before");
+    assert_eq!(toast(&app), "Renamed Tokyo to Home — press u to undo");
+
+    // An empty name changes nothing
+    app.open_rename_input();
+    app.input.clear();
+    app.apply_rename_input();
+    assert_eq!(names(&app), ["Paris", "Home"]);
+
+    app.undo();
+    assert_eq!(names(&app), ["Paris", "Tokyo"]);
+    app.redo();
+    assert_eq!(toast(&app), "Redone: Renamed Tokyo to Home");
+}
+
+#[test]
+fn a_renamed_city_keeps_its_refresh_interval() {
+    let mut refresh = RefreshSettings::default();
+    refresh.cities.insert("Tokyo".to_string(), 300);
+    let mut app = app_with(Config { cities: named(&["Paris", "Tokyo"]), refresh, ..Config::default() });
+    let five_minutes = Some(Duration::from_secs(300));
+    app.next_city();
+    app.open_rename_input();
+    app.input = "Home".to_string();
+    app.apply_rename_input();
+    assert_eq!(app.refresh.interval_for(&app.cities[1]), five_minutes);
+    assert_eq!(app.current_config().refresh.cities.keys().collect::<Vec<_>>(), ["Home"]);
+
+    app.undo();
+    assert_eq!(app.refresh.interval_for(&app.cities[1]), five_minutes);
+    assert!(app.refresh.cities.contains_key("Tokyo"));
+}